@@ -0,0 +1,127 @@
+use bincode::Options;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize)]
+enum Protocol {
+    Http,
+    Https { port: u16 },
+}
+
+#[derive(Serialize, Deserialize)]
+struct Server {
+    name: String,
+    addr: String,
+    protocol: Protocol,
+    weights: Vec<f64>,
+    tags: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Config {
+    version: u32,
+    servers: Vec<Server>,
+}
+
+fn config() -> Config {
+    Config {
+        version: 3,
+        servers: (0..32)
+            .map(|i| Server {
+                name: format!("server-{}", i),
+                addr: format!("10.0.0.{}", i),
+                protocol: if i % 2 == 0 {
+                    Protocol::Http
+                } else {
+                    Protocol::Https { port: 8443 }
+                },
+                weights: vec![0.5; 8],
+                tags: vec!["prod".into(), "eu-west".into()],
+            })
+            .collect(),
+    }
+}
+
+fn no_op(_: &serde_log::Event<'_>) {}
+
+fn json(c: &mut Criterion) {
+    let input = serde_json::to_vec(&config()).unwrap();
+    let mut group = c.benchmark_group("json");
+    group.bench_function(BenchmarkId::new("deserialize", "plain"), |b| {
+        b.iter(|| {
+            let mut de = serde_json::Deserializer::from_slice(black_box(&input));
+            Config::deserialize(&mut de).unwrap()
+        })
+    });
+    group.bench_function(BenchmarkId::new("deserialize", "disabled"), |b| {
+        b.iter(|| {
+            let mut de = serde_json::Deserializer::from_slice(black_box(&input));
+            serde_log::deserialize::<_, Config>(&mut de).unwrap()
+        })
+    });
+    group.bench_function(BenchmarkId::new("deserialize", "no-op sink"), |b| {
+        b.iter(|| {
+            let mut de = serde_json::Deserializer::from_slice(black_box(&input));
+            let de = serde_log::Deserializer::with_sink(&mut de, serde_log::sink::from_fn(no_op));
+            Config::deserialize(de).unwrap()
+        })
+    });
+
+    let value = config();
+    group.bench_function(BenchmarkId::new("serialize", "plain"), |b| {
+        b.iter(|| {
+            let mut out = Vec::with_capacity(input.len());
+            black_box(&value)
+                .serialize(&mut serde_json::Serializer::new(&mut out))
+                .unwrap();
+            out
+        })
+    });
+    group.bench_function(BenchmarkId::new("serialize", "disabled"), |b| {
+        b.iter(|| {
+            let mut out = Vec::with_capacity(input.len());
+            serde_log::serialize(
+                black_box(&value),
+                &mut serde_json::Serializer::new(&mut out),
+            )
+            .unwrap();
+            out
+        })
+    });
+    group.finish();
+}
+
+fn bincode(c: &mut Criterion) {
+    let input = bincode::serialize(&config()).unwrap();
+    let mut group = c.benchmark_group("bincode");
+    group.bench_function(BenchmarkId::new("deserialize", "plain"), |b| {
+        b.iter(|| bincode::deserialize::<Config>(black_box(&input)).unwrap())
+    });
+    group.bench_function(BenchmarkId::new("deserialize", "disabled"), |b| {
+        b.iter(|| {
+            let mut de = bincode::Deserializer::from_slice(
+                black_box(&input),
+                bincode::config::DefaultOptions::new()
+                    .with_fixint_encoding()
+                    .allow_trailing_bytes(),
+            );
+            serde_log::deserialize::<_, Config>(&mut de).unwrap()
+        })
+    });
+    group.bench_function(BenchmarkId::new("deserialize", "no-op sink"), |b| {
+        b.iter(|| {
+            let mut de = bincode::Deserializer::from_slice(
+                black_box(&input),
+                bincode::config::DefaultOptions::new()
+                    .with_fixint_encoding()
+                    .allow_trailing_bytes(),
+            );
+            let de = serde_log::Deserializer::with_sink(&mut de, serde_log::sink::from_fn(no_op));
+            Config::deserialize(de).unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, json, bincode);
+criterion_main!(benches);
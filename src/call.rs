@@ -0,0 +1,62 @@
+use crate::{context::Context, Event, Kind, Outcome, Path, Wrapper};
+use std::{fmt, sync::Arc, time::Instant};
+
+/// A call whose enter event has been emitted, waiting on its result to emit the exit event.
+pub(crate) struct Call {
+    ctx: Arc<Context>,
+    kind: Kind,
+    method: &'static str,
+    type_name: Option<&'static str>,
+    scope: Option<Arc<str>>,
+    depth: u32,
+    path: Path,
+    redacted: bool,
+    start: Instant,
+}
+
+impl Call {
+    #[inline]
+    pub(crate) fn new<A>(
+        wrapper: &Wrapper<A>,
+        kind: Kind,
+        method: &'static str,
+        type_name: Option<&'static str>,
+    ) -> Self {
+        Self {
+            ctx: wrapper.ctx.clone(),
+            kind,
+            method,
+            type_name,
+            scope: wrapper.scope.clone(),
+            depth: wrapper.depth,
+            path: wrapper.path.clone(),
+            redacted: wrapper.redacted,
+            start: Instant::now(),
+        }
+    }
+
+    pub(crate) fn exit<T, E>(self, result: Result<T, E>) -> Result<T, E>
+    where
+        E: fmt::Display,
+    {
+        let elapsed = self.start.elapsed();
+        let outcome = match &result {
+            Ok(_) => Outcome::Ok,
+            Err(e) => Outcome::Err(self.ctx.redact_error(e.to_string(), self.redacted).into()),
+        };
+        self.ctx.emit(&Event {
+            kind: self.kind,
+            method: self.method,
+            type_name: self.type_name,
+            scope: self.scope.as_deref(),
+            depth: self.depth,
+            path: &self.path,
+            args: &[],
+            value: None,
+            outcome: Some(&outcome),
+            elapsed: Some(elapsed),
+            enter: false,
+        });
+        self.ctx.check(&self.path, result)
+    }
+}
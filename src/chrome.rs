@@ -0,0 +1,203 @@
+use crate::{
+    sink::{Sink, Truncated},
+    Event, Kind,
+};
+use std::{
+    fmt::{self, Write as _},
+    io,
+    sync::Mutex,
+    time::Instant,
+};
+
+/// Writes the events as Chrome Trace Event JSON, to load in `chrome://tracing` or Perfetto.
+///
+/// Calls become duration events and visitor calls become instant events carrying the visited
+/// value, named after the struct they are in and their path. [`finish`](Self::finish) completes
+/// the JSON once done. Values are cut at 64 characters or bytes by default, see
+/// [`max_value_len`](Self::max_value_len).
+///
+/// ```
+/// use std::sync::Arc;
+///
+/// let chrome = Arc::new(serde_log::ChromeTrace::new(Vec::new()));
+/// let mut json = serde_json::Deserializer::from_str("[1, 2, 3]");
+/// let de = serde_log::Deserializer::with_sink(&mut json, chrome.clone());
+/// let value: Vec<u32> = serde::Deserialize::deserialize(de).unwrap();
+/// let json = std::sync::Arc::try_unwrap(chrome).unwrap().into_inner().unwrap();
+/// ```
+pub struct ChromeTrace<W> {
+    start: Instant,
+    max_value_len: Option<usize>,
+    state: Mutex<State<W>>,
+}
+
+struct State<W> {
+    writer: W,
+    events: usize,
+    error: Option<io::Error>,
+    finished: bool,
+}
+
+impl<W> ChromeTrace<W>
+where
+    W: io::Write,
+{
+    pub fn new(writer: W) -> Self {
+        Self {
+            start: Instant::now(),
+            max_value_len: Some(crate::MAX_VALUE_LEN),
+            state: Mutex::new(State {
+                writer,
+                events: 0,
+                error: None,
+                finished: false,
+            }),
+        }
+    }
+
+    /// Cuts the strings and bytes written to `len` characters or bytes, `None` writes them whole.
+    pub fn max_value_len(mut self, len: Option<usize>) -> Self {
+        self.max_value_len = len;
+        self
+    }
+
+    /// Closes the JSON array and flushes the writer, returning the first error writing to it
+    /// if any. The events received afterwards are dropped.
+    pub fn finish(&self) -> io::Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(e) = state.error.take() {
+            return Err(e);
+        }
+        if state.finished {
+            return Ok(());
+        }
+        state.finished = true;
+        let end: &[u8] = if state.events == 0 { b"[]\n" } else { b"\n]\n" };
+        state.writer.write_all(end)?;
+        state.writer.flush()
+    }
+
+    /// Finishes the JSON and returns the writer.
+    pub fn into_inner(self) -> io::Result<W> {
+        self.finish()?;
+        Ok(self.state.into_inner().unwrap().writer)
+    }
+}
+
+impl<W> Sink for ChromeTrace<W>
+where
+    W: io::Write + Send,
+{
+    fn event(&self, event: &Event<'_>) {
+        // Calls become duration events, except visitor calls which are instants carrying the
+        // visited value. Every other event is an instant too.
+        let phase = match (event.kind, event.is_enter(), event.is_exit()) {
+            (Kind::Visitor, _, true) => return,
+            (Kind::Visitor, _, false) => "i",
+            (_, true, _) => "B",
+            (_, false, true) => "E",
+            (_, false, false) => "i",
+        };
+        let ts = self.start.elapsed().as_secs_f64() * 1e6;
+        let json = render(event, phase, ts, self.max_value_len).expect("writing to a String");
+
+        let mut state = self.state.lock().unwrap();
+        if state.finished || state.error.is_some() {
+            return;
+        }
+        let separator: &[u8] = if state.events == 0 { b"[\n" } else { b",\n" };
+        state.events += 1;
+        if let Err(e) = state
+            .writer
+            .write_all(separator)
+            .and_then(|_| state.writer.write_all(json.as_bytes()))
+        {
+            state.error = Some(e);
+        }
+    }
+}
+
+impl<W> fmt::Debug for ChromeTrace<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChromeTrace").finish()
+    }
+}
+
+fn render(
+    event: &Event<'_>,
+    phase: &str,
+    ts: f64,
+    max_value_len: Option<usize>,
+) -> Result<String, fmt::Error> {
+    let mut out = String::new();
+    out.push_str("{\"name\":");
+    match event.scope {
+        Some(scope) => string(&mut out, format_args!("{} {}", scope, event.path))?,
+        None => string(&mut out, format_args!("{}", event.path))?,
+    }
+    out.push_str(",\"cat\":");
+    string(&mut out, format_args!("{}", event.kind))?;
+    write!(
+        out,
+        ",\"ph\":\"{}\",\"ts\":{:.3},\"pid\":1,\"tid\":1",
+        phase, ts
+    )?;
+    if phase == "i" {
+        out.push_str(",\"s\":\"t\"");
+    }
+    out.push_str(",\"args\":{\"method\":");
+    string(&mut out, format_args!("{}", event.method))?;
+    if let Some(type_name) = event.type_name {
+        out.push_str(",\"type\":");
+        string(&mut out, format_args!("{}", type_name))?;
+    }
+    for (name, value) in event.args {
+        out.push(',');
+        string(&mut out, format_args!("{}", name))?;
+        out.push(':');
+        string(
+            &mut out,
+            format_args!("{}", Truncated(value, max_value_len)),
+        )?;
+    }
+    if let Some(value) = event.value {
+        out.push_str(",\"value\":");
+        string(
+            &mut out,
+            format_args!("{}", Truncated(value, max_value_len)),
+        )?;
+    }
+    if let Some(outcome) = event.outcome {
+        out.push_str(",\"outcome\":");
+        string(&mut out, format_args!("{}", outcome))?;
+    }
+    out.push_str("}}");
+    Ok(out)
+}
+
+/// Writes `args` as a JSON string.
+fn string(out: &mut String, args: fmt::Arguments<'_>) -> fmt::Result {
+    struct Escape<'a>(&'a mut String);
+
+    impl fmt::Write for Escape<'_> {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            for c in s.chars() {
+                match c {
+                    '"' => self.0.push_str("\\\""),
+                    '\\' => self.0.push_str("\\\\"),
+                    '\n' => self.0.push_str("\\n"),
+                    '\r' => self.0.push_str("\\r"),
+                    '\t' => self.0.push_str("\\t"),
+                    c if c.is_control() => write!(self.0, "\\u{:04x}", c as u32)?,
+                    c => self.0.push(c),
+                }
+            }
+            Ok(())
+        }
+    }
+
+    out.push('"');
+    Escape(out).write_fmt(args)?;
+    out.push('"');
+    Ok(())
+}
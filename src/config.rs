@@ -0,0 +1,271 @@
+use crate::{
+    context::Context,
+    redact::Rules,
+    sample::Sampling,
+    sink::{Collector, LogSink, Sink},
+    Error, Event, Kind, Redaction, Trace, Wrapper,
+};
+use serde::de;
+use std::{borrow::Cow, sync::Arc, time::Instant};
+
+/// Options for tracing a single (de)serialization.
+///
+/// ```
+/// let config = serde_log::Config::new()
+///     .level(log::Level::Debug)
+///     .max_depth(8)
+///     .visitor_calls(false);
+///
+/// let value: Vec<u32> = config
+///     .deserialize(&mut serde_json::Deserializer::from_str("[1, 2, 3]"))
+///     .unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Config {
+    level: log::Level,
+    target: Cow<'static, str>,
+    max_value_len: Option<usize>,
+    context_events: usize,
+    filter: Filter,
+    rules: Rules,
+    sampling: Option<Sampling>,
+    stats: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            level: log::Level::Trace,
+            target: Cow::Borrowed("serde_log"),
+            max_value_len: Some(crate::MAX_VALUE_LEN),
+            context_events: crate::CONTEXT_EVENTS,
+            filter: Default::default(),
+            rules: Default::default(),
+            sampling: None,
+            stats: false,
+        }
+    }
+}
+
+impl Config {
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The level events are logged at, `Trace` by default.
+    pub fn level(mut self, level: log::Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// The target events are logged to, `serde_log` by default.
+    pub fn target<S>(mut self, target: S) -> Self
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        self.target = target.into();
+        self
+    }
+
+    /// Skips the events nested deeper than `depth` wrappers.
+    pub fn max_depth(mut self, depth: u32) -> Self {
+        self.filter.max_depth = Some(depth);
+        self
+    }
+
+    /// Truncates the strings and bytes logged to `len` characters or bytes, 64 by default,
+    /// `None` logs them whole.
+    pub fn max_value_len(mut self, len: Option<usize>) -> Self {
+        self.max_value_len = len;
+        self
+    }
+
+    /// How many of the last events the errors of
+    /// [`deserialize_with_context`](Self::deserialize_with_context) keep, 16 by default.
+    pub fn context_events(mut self, count: usize) -> Self {
+        self.context_events = count;
+        self
+    }
+
+    /// Whether to emit the `Deserialize`, `Deserializer` and `DeserializeSeed` calls, and
+    /// their `Serialize` and `Serializer` counterparts.
+    pub fn deserializer_calls(mut self, enabled: bool) -> Self {
+        self.filter.deserializer = enabled;
+        self
+    }
+
+    /// Whether to emit the `Visitor` calls.
+    pub fn visitor_calls(mut self, enabled: bool) -> Self {
+        self.filter.visitor = enabled;
+        self
+    }
+
+    /// Whether to emit the `SeqAccess`, `MapAccess`, `EnumAccess` and `VariantAccess` calls,
+    /// and the calls on the `Serialize*` compound serializers.
+    pub fn access_calls(mut self, enabled: bool) -> Self {
+        self.filter.access = enabled;
+        self
+    }
+
+    /// Whether to emit an exit event when a call returns.
+    pub fn exit_events(mut self, enabled: bool) -> Self {
+        self.filter.exits = enabled;
+        self
+    }
+
+    /// Hides the values at the paths matching `rule`, and everything nested in them.
+    ///
+    /// Rules starting with a `/` are JSON pointers matched from the root, e.g. `/auth/**`. Others
+    /// are dot-separated and matched against the end of the path, e.g. `password` or `*.secret`.
+    /// `*` matches within a key and `**` matches any number of keys.
+    pub fn redact(mut self, rule: &str) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// How the redacted values are logged, [`Redaction::Mask`] by default.
+    pub fn redaction(mut self, redaction: Redaction) -> Self {
+        self.rules.set_redaction(redaction);
+        self
+    }
+
+    /// Only logs the first `head` and last `tail` elements of each sequence and map deserialized,
+    /// followed by an `elided` event with how many were left out and the time spent on the
+    /// whole collection.
+    pub fn sample(mut self, head: usize, tail: usize) -> Self {
+        self.sampling = Some(Sampling { head, tail });
+        self
+    }
+
+    /// Whether to log a [`Stats`](crate::Stats) summary once done deserializing, off by default.
+    pub fn stats(mut self, enabled: bool) -> Self {
+        self.stats = enabled;
+        self
+    }
+
+    /// Wraps a `Deserializer` or `Serializer` to trace it with this config.
+    pub fn wrap<A>(&self, inner: A) -> Wrapper<A> {
+        Wrapper::with_context(inner, Arc::new(self.context()))
+    }
+
+    /// Deserializes a `T` with this config, or directly when its level is disabled.
+    pub fn deserialize<'de, D, T>(&self, deserializer: D) -> Result<T, D::Error>
+    where
+        D: de::Deserializer<'de>,
+        T: de::Deserialize<'de>,
+    {
+        if !crate::enabled(self.level) {
+            return T::deserialize(deserializer);
+        }
+        if !self.stats {
+            return T::deserialize(self.wrap(deserializer).scoped_to::<T>());
+        }
+        let ctx = Arc::new(self.context().with_stats());
+        let start = Instant::now();
+        let wrapper = Wrapper::with_context(deserializer, ctx.clone()).scoped_to::<T>();
+        let result = T::deserialize(wrapper);
+        let stats = ctx.stats(&result, start.elapsed());
+        log::log!(target: &self.target, self.level, "Stats: {}", stats);
+        result
+    }
+
+    /// Deserializes a `T` like [`deserialize_with_context`](crate::deserialize_with_context),
+    /// with this config.
+    pub fn deserialize_with_context<'de, D, T>(&self, deserializer: D) -> Result<T, Error<D::Error>>
+    where
+        D: de::Deserializer<'de>,
+        T: de::Deserialize<'de>,
+    {
+        let ctx = Arc::new(self.context().with_history(self.context_events));
+        T::deserialize(Wrapper::with_context(deserializer, ctx.clone()).scoped_to::<T>())
+            .map_err(|e| Error::new(e, ctx.failure().unwrap_or_default(), ctx.history()))
+    }
+
+    /// Deserializes a `T` with this config, recording the events instead of logging them.
+    pub fn record<'de, D, T>(&self, deserializer: D) -> (Result<T, D::Error>, Trace)
+    where
+        D: de::Deserializer<'de>,
+        T: de::Deserialize<'de>,
+    {
+        let collector = Arc::new(Collector::new());
+        let ctx = Arc::new(self.context_with(collector.clone()));
+        let result = T::deserialize(Wrapper::with_context(deserializer, ctx).scoped_to::<T>());
+        (result, Trace::from(collector.take()))
+    }
+
+    /// Serializes `value` with this config, or directly when its level is disabled.
+    pub fn serialize<T, S>(&self, value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized + serde::Serialize,
+        S: serde::Serializer,
+    {
+        if !crate::enabled(self.level) {
+            return value.serialize(serializer);
+        }
+        value.serialize(self.wrap(serializer).scoped_to::<T>())
+    }
+
+    pub(crate) fn context(&self) -> Context {
+        let sink = LogSink::new(self.level, self.target.clone(), self.max_value_len);
+        #[cfg(feature = "tracing")]
+        let sink = (sink, crate::sink::TracingSink::new(self.max_value_len));
+        self.context_with(Arc::new(sink))
+    }
+
+    fn context_with(&self, sink: Arc<dyn Sink>) -> Context {
+        Context::new(sink)
+            .with_filter(self.filter.clone())
+            .with_rules(self.rules.clone())
+            .with_sampling(self.sampling)
+            .with_max_value_len(self.max_value_len)
+    }
+}
+
+/// Decides which events reach the sink.
+#[derive(Clone, Debug)]
+pub(crate) struct Filter {
+    max_depth: Option<u32>,
+    deserializer: bool,
+    visitor: bool,
+    access: bool,
+    exits: bool,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            deserializer: true,
+            visitor: true,
+            access: true,
+            exits: true,
+        }
+    }
+}
+
+impl Filter {
+    pub(crate) fn allows(&self, event: &Event<'_>) -> bool {
+        let kind = match event.kind {
+            Kind::Deserialize
+            | Kind::Deserializer
+            | Kind::DeserializeSeed
+            | Kind::Serialize
+            | Kind::Serializer => self.deserializer,
+            Kind::Visitor => self.visitor,
+            Kind::SeqAccess
+            | Kind::MapAccess
+            | Kind::EnumAccess
+            | Kind::VariantAccess
+            | Kind::SerializeSeq
+            | Kind::SerializeTuple
+            | Kind::SerializeTupleStruct
+            | Kind::SerializeTupleVariant
+            | Kind::SerializeMap
+            | Kind::SerializeStruct
+            | Kind::SerializeStructVariant => self.access,
+        };
+        kind && (self.exits || !event.is_exit())
+            && !matches!(self.max_depth, Some(max) if event.depth > max)
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    config::Filter,
+    redact::Rules,
+    sample::Sampling,
+    sink::{Line, Sink, Truncated},
+    stats::Tally,
+    Config, Event, Outcome, Path, Record, Stats, Value,
+};
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+pub(crate) struct Context {
+    sink: Arc<dyn Sink>,
+    filter: Filter,
+    rules: Rules,
+    sampling: Option<Sampling>,
+    max_value_len: Option<usize>,
+    buffers: Mutex<Vec<Vec<Record>>>,
+    tally: Option<Mutex<Tally>>,
+    redacted_error: Mutex<Option<String>>,
+    capture: Mutex<Capture>,
+    history: Option<History>,
+    failed: AtomicBool,
+    failure: Mutex<Option<Path>>,
+}
+
+impl Default for Context {
+    fn default() -> Self {
+        Config::new().context()
+    }
+}
+
+#[derive(Default)]
+enum Capture {
+    #[default]
+    Idle,
+    Armed,
+    Captured(String),
+}
+
+struct History {
+    events: Mutex<VecDeque<String>>,
+    limit: usize,
+}
+
+impl Context {
+    pub(crate) fn new(sink: Arc<dyn Sink>) -> Self {
+        Self {
+            sink,
+            filter: Default::default(),
+            rules: Default::default(),
+            sampling: None,
+            max_value_len: Some(crate::MAX_VALUE_LEN),
+            buffers: Mutex::new(Vec::new()),
+            tally: None,
+            redacted_error: Mutex::new(None),
+            capture: Default::default(),
+            history: None,
+            failed: AtomicBool::new(false),
+            failure: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    pub(crate) fn with_rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    pub(crate) fn with_sampling(mut self, sampling: Option<Sampling>) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    pub(crate) fn with_max_value_len(mut self, max_value_len: Option<usize>) -> Self {
+        self.max_value_len = max_value_len;
+        self
+    }
+
+    pub(crate) fn with_stats(mut self) -> Self {
+        self.tally = Some(Default::default());
+        self
+    }
+
+    pub(crate) fn with_history(mut self, limit: usize) -> Self {
+        self.history = Some(History {
+            events: Mutex::new(VecDeque::with_capacity(limit)),
+            limit,
+        });
+        self
+    }
+
+    #[inline]
+    pub(crate) fn emit(&self, event: &Event<'_>) {
+        if let Some(tally) = &self.tally {
+            tally.lock().unwrap().add(event);
+        }
+        if self.filter.allows(event) {
+            self.send(event);
+        }
+        // Once failed, keep the events leading up to the failure rather than the unwinding.
+        if let Some(history) = self
+            .history
+            .as_ref()
+            .filter(|h| h.limit > 0 && !self.failed())
+        {
+            let mut events = history.events.lock().unwrap();
+            if events.len() == history.limit {
+                events.pop_front();
+            }
+            let line = Truncated(Line(event), self.max_value_len);
+            events.push_back(line.to_string());
+        }
+    }
+
+    /// Sends an event to the sink, or to the innermost buffer of a sampled collection.
+    #[inline]
+    fn send(&self, event: &Event<'_>) {
+        if self.sampling.is_some() {
+            if let Some(records) = self.buffers.lock().unwrap().last_mut() {
+                records.push(event.to_record());
+                return;
+            }
+        }
+        self.sink.event(event);
+    }
+
+    #[inline]
+    pub(crate) fn sampling(&self) -> Option<Sampling> {
+        self.sampling
+    }
+
+    pub(crate) fn begin_buffer(&self) {
+        self.buffers.lock().unwrap().push(Vec::new());
+    }
+
+    pub(crate) fn end_buffer(&self) -> Vec<Record> {
+        self.buffers.lock().unwrap().pop().unwrap_or_default()
+    }
+
+    /// Sends buffered events on, already filtered and kept in the history.
+    pub(crate) fn replay(&self, records: Vec<Record>) {
+        for record in records {
+            record.with_event(|event| self.send(event));
+        }
+    }
+
+    #[inline]
+    pub(crate) fn redacts(&self, path: &Path) -> bool {
+        self.rules.matches(path)
+    }
+
+    #[inline]
+    pub(crate) fn redact(&self, value: &Value<'_>) -> Value<'static> {
+        self.rules.redact(value)
+    }
+
+    /// Hides the message of an error raised on a redacted value, and of the same error as it
+    /// propagates through the calls above it since it often quotes the value.
+    pub(crate) fn redact_error(&self, message: String, redacted: bool) -> String {
+        if self.rules.is_empty() {
+            return message;
+        }
+        let mut last = self.redacted_error.lock().unwrap();
+        if redacted {
+            *last = Some(message);
+        } else if last.as_deref() != Some(&message) {
+            return message;
+        }
+        "<redacted>".to_owned()
+    }
+
+    #[inline]
+    pub(crate) fn arm_capture(&self) {
+        *self.capture.lock().unwrap() = Capture::Armed;
+    }
+
+    #[inline]
+    pub(crate) fn capture<F>(&self, f: F)
+    where
+        F: FnOnce() -> String,
+    {
+        let mut capture = self.capture.lock().unwrap();
+        if let Capture::Armed = *capture {
+            *capture = Capture::Captured(f());
+        }
+    }
+
+    #[inline]
+    pub(crate) fn take_capture(&self) -> Option<String> {
+        match std::mem::take(&mut *self.capture.lock().unwrap()) {
+            Capture::Captured(key) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Remembers the path of the innermost failing call, forgetting it again if the error
+    /// ends up being recovered from by an outer call.
+    #[inline]
+    pub(crate) fn check<T, E>(&self, path: &Path, result: Result<T, E>) -> Result<T, E> {
+        if self.history.is_some() {
+            match &result {
+                Ok(_) if self.failed() => {
+                    self.failed.store(false, Ordering::Relaxed);
+                    *self.failure.lock().unwrap() = None;
+                }
+                Err(_) if !self.failed() => {
+                    self.failed.store(true, Ordering::Relaxed);
+                    *self.failure.lock().unwrap() = Some(path.clone());
+                }
+                _ => {}
+            }
+        }
+        result
+    }
+
+    #[inline]
+    fn failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn failure(&self) -> Option<Path> {
+        self.failure.lock().unwrap().clone()
+    }
+
+    /// The stats of the events emitted so far, ending with `result`.
+    pub(crate) fn stats<T, E>(&self, result: &Result<T, E>, elapsed: Duration) -> Stats
+    where
+        E: fmt::Display,
+    {
+        let outcome = match result {
+            Ok(_) => Outcome::Ok,
+            Err(e) => Outcome::Err(self.redact_error(e.to_string(), false).into()),
+        };
+        self.tally
+            .as_ref()
+            .map(|tally| tally.lock().unwrap().finish(outcome.clone(), elapsed))
+            .unwrap_or_else(|| Stats {
+                outcome,
+                elapsed,
+                ..Default::default()
+            })
+    }
+
+    pub(crate) fn history(&self) -> Vec<String> {
+        self.history
+            .as_ref()
+            .map(|h| h.events.lock().unwrap().iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
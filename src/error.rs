@@ -0,0 +1,71 @@
+use crate::Path;
+use std::{error, fmt};
+
+/// An error from the wrapped deserializer, along with where it happened.
+pub struct Error<E> {
+    inner: E,
+    path: Path,
+    events: Vec<String>,
+}
+
+impl<E> Error<E> {
+    pub(crate) fn new(inner: E, path: Path, events: Vec<String>) -> Self {
+        Self {
+            inner,
+            path,
+            events,
+        }
+    }
+
+    #[inline]
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    #[inline]
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The trace events leading up to the error, oldest first.
+    #[inline]
+    pub fn events(&self) -> &[String] {
+        &self.events
+    }
+}
+
+impl<E> fmt::Display for Error<E>
+where
+    E: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.inner)
+    }
+}
+
+impl<E> fmt::Debug for Error<E>
+where
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Error")
+            .field("inner", &self.inner)
+            .field("path", &self.path)
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
+impl<E> error::Error for Error<E>
+where
+    E: error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.inner)
+    }
+}
@@ -0,0 +1,875 @@
+use crate::Path;
+use serde::{
+    de,
+    ser::{self, SerializeStruct as _},
+    Deserialize, Serialize,
+};
+use std::{borrow::Cow, fmt, marker::PhantomData, time::Duration};
+
+/// Declares the kinds along with their names, in the same order so the discriminant of a kind
+/// indexes both tables.
+macro_rules! kinds {
+    ($($variant:ident,)*) => {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum Kind {
+            $($variant,)*
+        }
+
+        const KINDS: &[Kind] = &[$(Kind::$variant,)*];
+
+        const KIND_NAMES: &[&str] = &[$(stringify!($variant),)*];
+    };
+}
+
+kinds! {
+    Deserialize,
+    Deserializer,
+    Visitor,
+    DeserializeSeed,
+    SeqAccess,
+    MapAccess,
+    EnumAccess,
+    VariantAccess,
+    Serialize,
+    Serializer,
+    SerializeSeq,
+    SerializeTuple,
+    SerializeTupleStruct,
+    SerializeTupleVariant,
+    SerializeMap,
+    SerializeStruct,
+    SerializeStructVariant,
+}
+
+impl Kind {
+    pub fn as_str(&self) -> &'static str {
+        KIND_NAMES[*self as usize]
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Kinds are (de)serialized as unit variants.
+impl Serialize for Kind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.serialize_unit_variant("Kind", *self as u32, self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Kind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct KindVisitor;
+
+        impl<'de> de::Visitor<'de> for KindVisitor {
+            type Value = Kind;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a call kind")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::EnumAccess<'de>,
+            {
+                let (index, variant) = data.variant_seed(Identifier::variant(KIND_NAMES))?;
+                de::VariantAccess::unit_variant(variant)?;
+                Ok(KINDS[index])
+            }
+        }
+
+        deserializer.deserialize_enum("Kind", KIND_NAMES, KindVisitor)
+    }
+}
+
+/// A value passed through the wrapper, either as a call argument or as visited data.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<'a> {
+    Bool(bool),
+    I8(i8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    I128(i128),
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    F32(f32),
+    F64(f64),
+    Char(char),
+    Str(Cow<'a, str>),
+    Bytes(Cow<'a, [u8]>),
+    Seq(Vec<Value<'a>>),
+    Duration(Duration),
+    /// A value hidden by a redaction rule, with its hash when using
+    /// [`Redaction::Hash`](crate::Redaction::Hash).
+    Redacted(Option<u64>),
+}
+
+impl Value<'_> {
+    pub fn borrowed(&self) -> Value<'_> {
+        match self {
+            Value::Str(v) => Value::Str(Cow::Borrowed(v)),
+            Value::Bytes(v) => Value::Bytes(Cow::Borrowed(v)),
+            Value::Seq(v) => Value::Seq(v.iter().map(Value::borrowed).collect()),
+            v => v.clone(),
+        }
+    }
+
+    pub fn into_owned(self) -> Value<'static> {
+        match self {
+            Value::Bool(v) => Value::Bool(v),
+            Value::I8(v) => Value::I8(v),
+            Value::I16(v) => Value::I16(v),
+            Value::I32(v) => Value::I32(v),
+            Value::I64(v) => Value::I64(v),
+            Value::I128(v) => Value::I128(v),
+            Value::U8(v) => Value::U8(v),
+            Value::U16(v) => Value::U16(v),
+            Value::U32(v) => Value::U32(v),
+            Value::U64(v) => Value::U64(v),
+            Value::U128(v) => Value::U128(v),
+            Value::F32(v) => Value::F32(v),
+            Value::F64(v) => Value::F64(v),
+            Value::Char(v) => Value::Char(v),
+            Value::Str(v) => Value::Str(Cow::Owned(v.into_owned())),
+            Value::Bytes(v) => Value::Bytes(Cow::Owned(v.into_owned())),
+            Value::Seq(v) => Value::Seq(v.into_iter().map(Value::into_owned).collect()),
+            Value::Duration(v) => Value::Duration(v),
+            Value::Redacted(v) => Value::Redacted(v),
+        }
+    }
+}
+
+/// Bytes are shown in hex. The precision, if any, caps how many characters or bytes of strings
+/// and bytes are shown, followed by their full length when cut.
+impl fmt::Display for Value<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Bool(v) => write!(f, "{}", v),
+            Value::I8(v) => write!(f, "{}", v),
+            Value::I16(v) => write!(f, "{}", v),
+            Value::I32(v) => write!(f, "{}", v),
+            Value::I64(v) => write!(f, "{}", v),
+            Value::I128(v) => write!(f, "{}", v),
+            Value::U8(v) => write!(f, "{}", v),
+            Value::U16(v) => write!(f, "{}", v),
+            Value::U32(v) => write!(f, "{}", v),
+            Value::U64(v) => write!(f, "{}", v),
+            Value::U128(v) => write!(f, "{}", v),
+            Value::F32(v) => write!(f, "{:?}", v),
+            Value::F64(v) => write!(f, "{:?}", v),
+            Value::Char(v) => write!(f, "{:?}", v),
+            Value::Str(v) => match f.precision().and_then(|max| v.char_indices().nth(max)) {
+                Some((end, _)) => write!(f, "{:?}…({} bytes)", &v[..end], v.len()),
+                None => write!(f, "{:?}", v),
+            },
+            Value::Bytes(v) => match f.precision() {
+                Some(max) if v.len() > max => {
+                    write!(f, "{}…({} bytes)", Hex(&v[..max]), v.len())
+                }
+                _ => write!(f, "{}", Hex(v)),
+            },
+            Value::Seq(v) => {
+                f.write_str("[")?;
+                for (i, v) in v.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    fmt::Display::fmt(v, f)?;
+                }
+                f.write_str("]")
+            }
+            Value::Duration(v) => write!(f, "{:.1?}", v),
+            Value::Redacted(None) => f.write_str("<redacted>"),
+            Value::Redacted(Some(hash)) => write!(f, "<redacted:{:016x}>", hash),
+        }
+    }
+}
+
+const VALUE_VARIANTS: &[&str] = &[
+    "Bool", "I8", "I16", "I32", "I64", "I128", "U8", "U16", "U32", "U64", "U128", "F32", "F64",
+    "Char", "Str", "Bytes", "Seq", "Duration", "Redacted",
+];
+
+/// Values are (de)serialized as newtype variants, bytes as a sequence.
+impl Serialize for Value<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        fn variant<S, T>(serializer: S, index: u32, value: &T) -> Result<S::Ok, S::Error>
+        where
+            S: ser::Serializer,
+            T: ?Sized + Serialize,
+        {
+            let name = VALUE_VARIANTS[index as usize];
+            serializer.serialize_newtype_variant("Value", index, name, value)
+        }
+
+        match self {
+            Value::Bool(v) => variant(serializer, 0, v),
+            Value::I8(v) => variant(serializer, 1, v),
+            Value::I16(v) => variant(serializer, 2, v),
+            Value::I32(v) => variant(serializer, 3, v),
+            Value::I64(v) => variant(serializer, 4, v),
+            Value::I128(v) => variant(serializer, 5, v),
+            Value::U8(v) => variant(serializer, 6, v),
+            Value::U16(v) => variant(serializer, 7, v),
+            Value::U32(v) => variant(serializer, 8, v),
+            Value::U64(v) => variant(serializer, 9, v),
+            Value::U128(v) => variant(serializer, 10, v),
+            Value::F32(v) => variant(serializer, 11, v),
+            Value::F64(v) => variant(serializer, 12, v),
+            Value::Char(v) => variant(serializer, 13, v),
+            Value::Str(v) => variant(serializer, 14, &**v),
+            Value::Bytes(v) => variant(serializer, 15, &**v),
+            Value::Seq(v) => variant(serializer, 16, v),
+            Value::Duration(v) => variant(serializer, 17, v),
+            Value::Redacted(v) => variant(serializer, 18, v),
+        }
+    }
+}
+
+impl<'de, 'a> Deserialize<'de> for Value<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct ValueVisitor<'a>(PhantomData<Value<'a>>);
+
+        impl<'de, 'a> de::Visitor<'de> for ValueVisitor<'a> {
+            type Value = Value<'a>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a value")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::EnumAccess<'de>,
+            {
+                use de::VariantAccess as _;
+
+                let (index, v) = data.variant_seed(Identifier::variant(VALUE_VARIANTS))?;
+                Ok(match index {
+                    0 => Value::Bool(v.newtype_variant()?),
+                    1 => Value::I8(v.newtype_variant()?),
+                    2 => Value::I16(v.newtype_variant()?),
+                    3 => Value::I32(v.newtype_variant()?),
+                    4 => Value::I64(v.newtype_variant()?),
+                    5 => Value::I128(v.newtype_variant()?),
+                    6 => Value::U8(v.newtype_variant()?),
+                    7 => Value::U16(v.newtype_variant()?),
+                    8 => Value::U32(v.newtype_variant()?),
+                    9 => Value::U64(v.newtype_variant()?),
+                    10 => Value::U128(v.newtype_variant()?),
+                    11 => Value::F32(v.newtype_variant()?),
+                    12 => Value::F64(v.newtype_variant()?),
+                    13 => Value::Char(v.newtype_variant()?),
+                    14 => Value::Str(Cow::Owned(v.newtype_variant()?)),
+                    15 => Value::Bytes(Cow::Owned(v.newtype_variant()?)),
+                    16 => Value::Seq(v.newtype_variant()?),
+                    17 => Value::Duration(v.newtype_variant()?),
+                    _ => Value::Redacted(v.newtype_variant()?),
+                })
+            }
+        }
+
+        deserializer.deserialize_enum("Value", VALUE_VARIANTS, ValueVisitor(PhantomData))
+    }
+}
+
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("0x")?;
+        for b in self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! impl_from {
+    ($($ty:ty => $variant:ident,)*) => {
+        $(
+            impl From<$ty> for Value<'_> {
+                #[inline]
+                fn from(v: $ty) -> Self {
+                    Value::$variant(v)
+                }
+            }
+        )*
+    };
+}
+
+impl_from! {
+    bool => Bool,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    i128 => I128,
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    u128 => U128,
+    f32 => F32,
+    f64 => F64,
+    char => Char,
+}
+
+impl From<usize> for Value<'_> {
+    #[inline]
+    fn from(v: usize) -> Self {
+        Value::U64(v as u64)
+    }
+}
+
+impl<'a> From<&'a str> for Value<'a> {
+    #[inline]
+    fn from(v: &'a str) -> Self {
+        Value::Str(Cow::Borrowed(v))
+    }
+}
+
+impl<'a> From<&'a [&'a str]> for Value<'a> {
+    #[inline]
+    fn from(v: &'a [&'a str]) -> Self {
+        Value::Seq(v.iter().map(|v| Value::from(*v)).collect())
+    }
+}
+
+impl<'a> From<&'a [u8]> for Value<'a> {
+    #[inline]
+    fn from(v: &'a [u8]) -> Self {
+        Value::Bytes(Cow::Borrowed(v))
+    }
+}
+
+/// How a call ended, carried by exit events.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum Outcome<'a> {
+    #[default]
+    Ok,
+    Err(Cow<'a, str>),
+}
+
+impl Outcome<'_> {
+    #[inline]
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Outcome::Ok)
+    }
+
+    #[inline]
+    pub fn is_err(&self) -> bool {
+        !self.is_ok()
+    }
+
+    pub fn into_owned(self) -> Outcome<'static> {
+        match self {
+            Outcome::Ok => Outcome::Ok,
+            Outcome::Err(e) => Outcome::Err(Cow::Owned(e.into_owned())),
+        }
+    }
+}
+
+impl fmt::Display for Outcome<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Ok => f.write_str("Ok"),
+            Outcome::Err(e) => write!(f, "Err({})", e),
+        }
+    }
+}
+
+const OUTCOME_VARIANTS: &[&str] = &["Ok", "Err"];
+
+impl Serialize for Outcome<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match self {
+            Outcome::Ok => serializer.serialize_unit_variant("Outcome", 0, "Ok"),
+            Outcome::Err(e) => serializer.serialize_newtype_variant("Outcome", 1, "Err", &**e),
+        }
+    }
+}
+
+impl<'de, 'a> Deserialize<'de> for Outcome<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct OutcomeVisitor<'a>(PhantomData<Outcome<'a>>);
+
+        impl<'de, 'a> de::Visitor<'de> for OutcomeVisitor<'a> {
+            type Value = Outcome<'a>;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("an outcome")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::EnumAccess<'de>,
+            {
+                use de::VariantAccess as _;
+
+                match data.variant_seed(Identifier::variant(OUTCOME_VARIANTS))? {
+                    (0, v) => v.unit_variant().map(|_| Outcome::Ok),
+                    (_, v) => v.newtype_variant().map(|e: String| Outcome::Err(e.into())),
+                }
+            }
+        }
+
+        deserializer.deserialize_enum("Outcome", OUTCOME_VARIANTS, OutcomeVisitor(PhantomData))
+    }
+}
+
+/// A single call intercepted by a [`Wrapper`](crate::Wrapper).
+#[derive(Clone, Copy, Debug)]
+#[non_exhaustive]
+pub struct Event<'a> {
+    pub kind: Kind,
+    pub method: &'a str,
+    /// The type being produced, e.g. the `Value` of the visitor passed to a `Deserializer`.
+    pub type_name: Option<&'a str>,
+    /// The innermost struct or enum the call is nested in, or the type (de)serialized at the top
+    /// level outside of them.
+    pub scope: Option<&'a str>,
+    pub depth: u32,
+    pub path: &'a Path,
+    pub args: &'a [(&'a str, Value<'a>)],
+    pub value: Option<&'a Value<'a>>,
+    /// Set on the exit event emitted once a call returns.
+    pub outcome: Option<&'a Outcome<'a>>,
+    /// Set on exit events, how long the call took including the calls nested in it.
+    pub elapsed: Option<Duration>,
+    pub(crate) enter: bool,
+}
+
+impl Event<'_> {
+    #[inline]
+    pub fn is_exit(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    /// Whether this is the enter event of a call, always followed by its exit event.
+    #[inline]
+    pub fn is_enter(&self) -> bool {
+        self.enter
+    }
+
+    pub fn to_record(&self) -> Record {
+        Record {
+            kind: self.kind,
+            method: self.method.to_owned().into(),
+            type_name: self.type_name.map(|t| t.to_owned().into()),
+            scope: self.scope.map(|s| s.to_owned().into()),
+            depth: self.depth,
+            path: self.path.clone(),
+            args: self
+                .args
+                .iter()
+                .map(|(name, value)| ((*name).to_owned().into(), value.clone().into_owned()))
+                .collect(),
+            value: self.value.map(|v| v.clone().into_owned()),
+            outcome: self.outcome.map(|o| o.clone().into_owned()),
+            elapsed: self.elapsed,
+            enter: self.enter,
+        }
+    }
+}
+
+impl fmt::Display for Event<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.kind, self.method)?;
+        if !self.args.is_empty() || self.value.is_some() {
+            f.write_str("(")?;
+            for (i, (name, value)) in self.args.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}=", name)?;
+                fmt::Display::fmt(value, f)?;
+            }
+            if let Some(value) = self.value {
+                if !self.args.is_empty() {
+                    f.write_str(", ")?;
+                }
+                fmt::Display::fmt(value, f)?;
+            }
+            f.write_str(")")?;
+        }
+        if let Some(type_name) = self.type_name {
+            write!(f, " -> {}", type_name)?;
+        }
+        if let Some(outcome) = self.outcome {
+            write!(f, " = {}", outcome)?;
+        }
+        Ok(())
+    }
+}
+
+/// An owned copy of an [`Event`].
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub struct Record {
+    pub kind: Kind,
+    pub method: Cow<'static, str>,
+    pub type_name: Option<Cow<'static, str>>,
+    pub scope: Option<Cow<'static, str>>,
+    pub depth: u32,
+    pub path: Path,
+    pub args: Vec<(Cow<'static, str>, Value<'static>)>,
+    pub value: Option<Value<'static>>,
+    pub outcome: Option<Outcome<'static>>,
+    pub elapsed: Option<Duration>,
+    pub(crate) enter: bool,
+}
+
+impl Record {
+    #[inline]
+    pub fn is_exit(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    /// Whether this is the enter event of a call, always followed by its exit event.
+    #[inline]
+    pub fn is_enter(&self) -> bool {
+        self.enter
+    }
+
+    /// Calls `f` with this record viewed as an [`Event`].
+    pub fn with_event<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&Event<'_>) -> R,
+    {
+        let args: Vec<_> = self
+            .args
+            .iter()
+            .map(|(name, value)| (name.as_ref(), value.borrowed()))
+            .collect();
+        f(&Event {
+            kind: self.kind,
+            method: &self.method,
+            type_name: self.type_name.as_deref(),
+            scope: self.scope.as_deref(),
+            depth: self.depth,
+            path: &self.path,
+            args: &args,
+            value: self.value.as_ref(),
+            outcome: self.outcome.as_ref(),
+            elapsed: self.elapsed,
+            enter: self.enter,
+        })
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.with_event(|event| fmt::Display::fmt(event, f))
+    }
+}
+
+const RECORD_FIELDS: &[&str] = &[
+    "kind",
+    "method",
+    "type_name",
+    "scope",
+    "depth",
+    "path",
+    "args",
+    "value",
+    "outcome",
+    "elapsed",
+    "enter",
+];
+
+/// Records are (de)serialized as structs, `enter` can be left out.
+impl Serialize for Record {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let mut record = serializer.serialize_struct("Record", RECORD_FIELDS.len())?;
+        record.serialize_field("kind", &self.kind)?;
+        record.serialize_field("method", &self.method)?;
+        record.serialize_field("type_name", &self.type_name)?;
+        record.serialize_field("scope", &self.scope)?;
+        record.serialize_field("depth", &self.depth)?;
+        record.serialize_field("path", &self.path)?;
+        record.serialize_field("args", &self.args)?;
+        record.serialize_field("value", &self.value)?;
+        record.serialize_field("outcome", &self.outcome)?;
+        record.serialize_field("elapsed", &self.elapsed)?;
+        record.serialize_field("enter", &self.enter)?;
+        record.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Record {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct RecordVisitor;
+
+        impl<'de> de::Visitor<'de> for RecordVisitor {
+            type Value = Record;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a record")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::SeqAccess<'de>,
+            {
+                fn next<'de, A, T>(seq: &mut A, index: usize) -> Result<T, A::Error>
+                where
+                    A: de::SeqAccess<'de>,
+                    T: Deserialize<'de>,
+                {
+                    seq.next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(index, &"a record"))
+                }
+
+                Ok(Record {
+                    kind: next(&mut seq, 0)?,
+                    method: next(&mut seq, 1)?,
+                    type_name: next(&mut seq, 2)?,
+                    scope: next(&mut seq, 3)?,
+                    depth: next(&mut seq, 4)?,
+                    path: next(&mut seq, 5)?,
+                    args: next(&mut seq, 6)?,
+                    value: next(&mut seq, 7)?,
+                    outcome: next(&mut seq, 8)?,
+                    elapsed: next(&mut seq, 9)?,
+                    enter: seq.next_element()?.unwrap_or_default(),
+                })
+            }
+
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let mut kind = None;
+                let mut method = None;
+                let mut type_name = None;
+                let mut scope = None;
+                let mut depth = None;
+                let mut path = None;
+                let mut args = None;
+                let mut value = None;
+                let mut outcome = None;
+                let mut elapsed = None;
+                let mut enter = None;
+                while let Some(field) = map.next_key_seed(Identifier::field(RECORD_FIELDS))? {
+                    match field {
+                        0 => kind = Some(map.next_value()?),
+                        1 => method = Some(map.next_value()?),
+                        2 => type_name = Some(map.next_value()?),
+                        3 => scope = Some(map.next_value()?),
+                        4 => depth = Some(map.next_value()?),
+                        5 => path = Some(map.next_value()?),
+                        6 => args = Some(map.next_value()?),
+                        7 => value = Some(map.next_value()?),
+                        8 => outcome = Some(map.next_value()?),
+                        9 => elapsed = Some(map.next_value()?),
+                        10 => enter = Some(map.next_value()?),
+                        _ => {
+                            map.next_value::<de::IgnoredAny>()?;
+                        }
+                    }
+                }
+                let missing = de::Error::missing_field;
+                Ok(Record {
+                    kind: kind.ok_or_else(|| missing("kind"))?,
+                    method: method.ok_or_else(|| missing("method"))?,
+                    type_name: type_name.ok_or_else(|| missing("type_name"))?,
+                    scope: scope.ok_or_else(|| missing("scope"))?,
+                    depth: depth.ok_or_else(|| missing("depth"))?,
+                    path: path.ok_or_else(|| missing("path"))?,
+                    args: args.ok_or_else(|| missing("args"))?,
+                    // Options may be left out, as with the derived impls.
+                    value: value.unwrap_or_default(),
+                    outcome: outcome.unwrap_or_default(),
+                    elapsed: elapsed.unwrap_or_default(),
+                    enter: enter.unwrap_or_default(),
+                })
+            }
+        }
+
+        deserializer.deserialize_struct("Record", RECORD_FIELDS, RecordVisitor)
+    }
+}
+
+/// Reads a variant or field name, or its index, as an index into `names`.
+///
+/// Unknown fields read as `names.len()` so they can be skipped, unknown
+/// variants are an error.
+pub(crate) struct Identifier {
+    names: &'static [&'static str],
+    fields: bool,
+}
+
+impl Identifier {
+    pub(crate) fn variant(names: &'static [&'static str]) -> Self {
+        Identifier {
+            names,
+            fields: false,
+        }
+    }
+
+    pub(crate) fn field(names: &'static [&'static str]) -> Self {
+        Identifier {
+            names,
+            fields: true,
+        }
+    }
+}
+
+impl<'de> de::DeserializeSeed<'de> for Identifier {
+    type Value = usize;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> de::Visitor<'de> for Identifier {
+    type Value = usize;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(if self.fields { "a field" } else { "a variant" })
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match v {
+            v if v < self.names.len() as u64 => Ok(v as usize),
+            _ if self.fields => Ok(self.names.len()),
+            _ => Err(E::invalid_value(de::Unexpected::Unsigned(v), &self)),
+        }
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match self.names.iter().position(|name| *name == v) {
+            Some(index) => Ok(index),
+            None if self.fields => Ok(self.names.len()),
+            None => Err(E::unknown_variant(v, self.names)),
+        }
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        match std::str::from_utf8(v) {
+            Ok(v) => self.visit_str(v),
+            Err(_) if self.fields => Ok(self.names.len()),
+            Err(_) => Err(E::invalid_value(de::Unexpected::Bytes(v), &self)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn str(s: &str) -> Value<'_> {
+        Value::Str(s.into())
+    }
+
+    fn bytes(b: &[u8]) -> Value<'_> {
+        Value::Bytes(b.into())
+    }
+
+    #[test]
+    fn str_within_limit() {
+        assert_eq!(format!("{}", str("abc")), r#""abc""#);
+        assert_eq!(format!("{:.5}", str("abc")), r#""abc""#);
+        assert_eq!(format!("{:.3}", str("abc")), r#""abc""#);
+        assert_eq!(format!("{:.0}", str("")), r#""""#);
+    }
+
+    #[test]
+    fn str_over_limit() {
+        assert_eq!(format!("{:.2}", str("abc")), r#""ab"…(3 bytes)"#);
+        assert_eq!(format!("{:.0}", str("abc")), r#"""…(3 bytes)"#);
+        // Escapes are kept, and don't count towards the limit.
+        assert_eq!(format!("{:.2}", str("a\"b")), r#""a\""…(3 bytes)"#);
+    }
+
+    #[test]
+    fn str_cut_on_char_boundary() {
+        // 2, 3 and 4 byte characters are kept whole, the limit counts characters.
+        assert_eq!(format!("{:.2}", str("héllo")), r#""hé"…(6 bytes)"#);
+        assert_eq!(format!("{:.1}", str("€€")), r#""€"…(6 bytes)"#);
+        assert_eq!(format!("{:.2}", str("🦀🦀🦀")), r#""🦀🦀"…(12 bytes)"#);
+        assert_eq!(format!("{:.3}", str("🦀🦀🦀")), r#""🦀🦀🦀""#);
+    }
+
+    #[test]
+    fn bytes_as_hex() {
+        assert_eq!(format!("{}", bytes(&[])), "0x");
+        assert_eq!(format!("{}", bytes(&[0, 1, 0xab, 0xff])), "0x0001abff");
+        assert_eq!(format!("{:.4}", bytes(&[0, 1, 0xab, 0xff])), "0x0001abff");
+        assert_eq!(format!("{:.5}", bytes(&[0, 1, 0xab, 0xff])), "0x0001abff");
+        assert_eq!(
+            format!("{:.2}", bytes(&[0, 1, 0xab, 0xff])),
+            "0x0001…(4 bytes)"
+        );
+        assert_eq!(format!("{:.0}", bytes(&[0xff])), "0x…(1 bytes)");
+    }
+
+    #[test]
+    fn nested_limits() {
+        let seq = Value::Seq(vec![str("abcd"), bytes(&[1, 2, 3]), Value::U8(7)]);
+        assert_eq!(
+            format!("{:.2}", seq),
+            r#"["ab"…(4 bytes), 0x0102…(3 bytes), 7]"#
+        );
+        assert_eq!(format!("{}", seq), r#"["abcd", 0x010203, 7]"#);
+    }
+
+    #[test]
+    fn kinds_round_trip() {
+        assert_eq!(KINDS.len(), KIND_NAMES.len());
+        for (index, kind) in KINDS.iter().enumerate() {
+            assert_eq!(*kind as usize, index);
+            // By name in self-describing formats, by index in the others.
+            let json = serde_json::to_string(kind).unwrap();
+            assert_eq!(json, format!("{:?}", format!("{:?}", kind)));
+            assert_eq!(serde_json::from_str::<Kind>(&json).unwrap(), *kind);
+            let bytes = bincode::serialize(kind).unwrap();
+            assert_eq!(bytes, (index as u32).to_le_bytes());
+            assert_eq!(bincode::deserialize::<Kind>(&bytes).unwrap(), *kind);
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::{profile::CallStack, sink::Sink, Event, Kind, Path, Segment, Value};
+use std::{
+    collections::HashMap,
+    io,
+    sync::{Arc, Mutex},
+};
+
+/// What the stacks written by [`FoldedStacks`] are weighted by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Weight {
+    /// The time spent in the calls, excluding the nested ones, in nanoseconds.
+    #[default]
+    Nanos,
+    /// The number of calls.
+    Calls,
+    /// The length of the strings and bytes visited.
+    Bytes,
+}
+
+/// Aggregates the events into folded stacks, as read by `inferno` and `flamegraph.pl`.
+///
+/// Each frame is either the name of a struct or enum being deserialized, or a field or index of
+/// its path, e.g. `Config;servers;0;Server;tls;TlsConfig;cert_path 1234`.
+#[derive(Debug)]
+pub struct FoldedStacks {
+    weight: Weight,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    calls: CallStack<Frame>,
+    stacks: HashMap<Arc<str>, u64>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    path: Path,
+    stack: Arc<str>,
+}
+
+impl FoldedStacks {
+    pub fn new(weight: Weight) -> Self {
+        Self {
+            weight,
+            state: Default::default(),
+        }
+    }
+
+    /// Writes one line per stack, sorted so that the output is stable.
+    pub fn write_to<W>(&self, mut writer: W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        let state = self.state.lock().unwrap();
+        // The calls made before reaching any struct or field have no frame to go under.
+        let mut stacks: Vec<_> = state
+            .stacks
+            .iter()
+            .filter(|(stack, weight)| !stack.is_empty() && **weight > 0)
+            .collect();
+        stacks.sort();
+        for (stack, weight) in stacks {
+            writeln!(writer, "{} {}", stack, weight)?;
+        }
+        writer.flush()
+    }
+}
+
+impl Sink for FoldedStacks {
+    fn event(&self, event: &Event<'_>) {
+        let mut state = self.state.lock().unwrap();
+        if event.is_exit() {
+            if let Some((frame, self_time)) = state.calls.exit(event) {
+                let weight = match self.weight {
+                    Weight::Nanos => self_time.as_nanos() as u64,
+                    Weight::Calls => 1,
+                    Weight::Bytes => 0,
+                };
+                *state.stacks.entry(frame.stack).or_default() += weight;
+            }
+            return;
+        }
+        if !event.is_enter() {
+            return;
+        }
+        let frame = frame(state.calls.last(), event);
+        if self.weight == Weight::Bytes && event.kind == Kind::Visitor {
+            let len = match event.value {
+                Some(Value::Str(v)) => v.len(),
+                Some(Value::Bytes(v)) => v.len(),
+                _ => 0,
+            };
+            *state.stacks.entry(frame.stack.clone()).or_default() += len as u64;
+        }
+        state.calls.enter(event, frame);
+    }
+}
+
+/// Extends the stack of the parent call with the segments its path gained and the struct or
+/// enum deserialized, if any.
+fn frame(parent: Option<&Frame>, event: &Event<'_>) -> Frame {
+    let mut stack = parent.map(|p| p.stack.to_string()).unwrap_or_default();
+    let skip = parent.map_or(0, |p| p.path.segments().len());
+    let mut push = |name: &str| {
+        if !stack.is_empty() {
+            stack.push(';');
+        }
+        // `;` separates frames and the last space the weight.
+        stack.extend(name.chars().map(|c| match c {
+            ';' | ' ' => '_',
+            c => c,
+        }));
+    };
+    for segment in event.path.segments().into_iter().skip(skip) {
+        match segment {
+            Segment::Key(key) | Segment::Variant(key) => push(key),
+            Segment::Index(index) => push(&index.to_string()),
+        }
+    }
+    if event.kind == Kind::Deserializer && event.method.starts_with("deserialize_") {
+        let name = event
+            .args
+            .iter()
+            .find_map(|(arg, value)| match (*arg, value) {
+                ("name", Value::Str(name)) => Some(name),
+                _ => None,
+            });
+        if let Some(name) = name {
+            push(name);
+        }
+    }
+    let stack = match parent {
+        Some(p) if *p.stack == *stack => p.stack.clone(),
+        _ => stack.into(),
+    };
+    Frame {
+        path: event.path.clone(),
+        stack,
+    }
+}
+
+#[cfg(all(test, not(feature = "disabled")))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_frames_while_serializing() {
+        // The serializer events never exit.
+        let stacks = Arc::new(FoldedStacks::new(Weight::Calls));
+        let mut json = serde_json::Serializer::new(Vec::new());
+        let serializer = crate::Serializer::with_sink(&mut json, stacks.clone());
+        serde::Serialize::serialize(&vec![1u8; 100], serializer).unwrap();
+        assert!(stacks.state.lock().unwrap().calls.last().is_none());
+    }
+}
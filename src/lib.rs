@@ -1,18 +1,173 @@
 use serde::de;
-use std::{fmt, ops};
+use std::{any::type_name, fmt, marker::PhantomData, ops, sync::Arc, time::Instant};
+
+mod call;
+mod chrome;
+mod config;
+mod context;
+mod error;
+mod event;
+mod folded;
+#[cfg(feature = "disabled")]
+mod passthrough;
+mod path;
+mod profile;
+mod redact;
+mod replay;
+mod sample;
+mod ser;
+pub mod sink;
+#[doc(hidden)]
+pub mod snapshot;
+#[cfg(not(feature = "disabled"))]
+mod span;
+mod stats;
+#[cfg(feature = "serde_test")]
+mod tokens;
+mod trace;
+
+#[cfg(not(feature = "disabled"))]
+use crate::span::Span;
+use crate::{call::Call, context::Context, sample::Sample};
+
+pub use crate::{
+    chrome::ChromeTrace,
+    config::Config,
+    error::Error,
+    event::{Event, Kind, Outcome, Record, Value},
+    folded::{FoldedStacks, Weight},
+    path::{Path, Segment},
+    profile::{Profile, Report, Timing},
+    redact::Redaction,
+    replay::{ReplayDeserializer, ReplayError},
+    sink::Sink,
+    stats::Stats,
+    trace::Trace,
+};
+
+const CONTEXT_EVENTS: usize = 16;
+const MAX_VALUE_LEN: usize = 64;
+
+/// Whether events at `level` could be logged at all, checked once per entry point so that
+/// nothing gets wrapped when they could not.
+#[inline]
+pub(crate) fn enabled(level: log::Level) -> bool {
+    if cfg!(feature = "disabled") {
+        return false;
+    }
+    #[cfg(feature = "tracing")]
+    {
+        if tracing::level_enabled!(tracing::Level::TRACE) {
+            return true;
+        }
+    }
+    level <= log::STATIC_MAX_LEVEL && level <= log::max_level()
+}
 
+/// Deserializes a `T`, logging every call at the trace level.
+///
+/// When trace logging is disabled this is the same as calling `T::deserialize` directly.
 #[inline]
 pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
 where
     D: de::Deserializer<'de>,
     T: de::Deserialize<'de>,
 {
-    T::deserialize(Deserializer::new(deserializer))
+    if !enabled(log::Level::Trace) {
+        return T::deserialize(deserializer);
+    }
+    T::deserialize(Deserializer::new(deserializer).scoped_to::<T>())
+}
+
+/// Deserializes into an existing `place`, going through `T`'s in-place path so allocations
+/// can be reused just like without the wrapper.
+#[inline]
+pub fn deserialize_in_place<'de, D, T>(deserializer: D, place: &mut T) -> Result<(), D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: de::Deserialize<'de>,
+{
+    if !enabled(log::Level::Trace) {
+        return T::deserialize_in_place(deserializer, place);
+    }
+    let deserializer = Deserializer::new(deserializer).scoped_to::<T>();
+    let call = deserializer.enter(
+        Kind::Deserialize,
+        "deserialize_in_place",
+        Some(type_name::<T>()),
+        &[],
+        None,
+    );
+    call.exit(T::deserialize_in_place(deserializer, place))
+}
+
+/// Deserializes a `T` through the wrapper whether logging is enabled or not, returning errors
+/// with the path they happened at and the last events leading up to them.
+pub fn deserialize_with_context<'de, D, T>(deserializer: D) -> Result<T, Error<D::Error>>
+where
+    D: de::Deserializer<'de>,
+    T: de::Deserialize<'de>,
+{
+    Config::new().deserialize_with_context(deserializer)
+}
+
+/// Deserializes a `T` like [`deserialize`], also returning a summary of the calls made.
+///
+/// Unlike [`deserialize`] this always goes through the wrapper, whether logging is enabled or
+/// not.
+pub fn deserialize_with_stats<'de, D, T>(deserializer: D) -> (Result<T, D::Error>, Stats)
+where
+    D: de::Deserializer<'de>,
+    T: de::Deserialize<'de>,
+{
+    let ctx = Arc::new(Context::default().with_stats());
+    let start = Instant::now();
+    let wrapper = Wrapper::with_context(deserializer, ctx.clone()).scoped_to::<T>();
+    let result = T::deserialize(wrapper);
+    let stats = ctx.stats(&result, start.elapsed());
+    (result, stats)
+}
+
+/// Deserializes a `T`, recording every call made through the wrapper instead of logging it.
+pub fn record<'de, D, T>(deserializer: D) -> (Result<T, D::Error>, Trace)
+where
+    D: de::Deserializer<'de>,
+    T: de::Deserialize<'de>,
+{
+    Config::new().record(deserializer)
+}
+
+/// Deserializes a `T`, timing every call made through the wrapper instead of logging it.
+pub fn profile<'de, D, T>(deserializer: D) -> (Result<T, D::Error>, Profile)
+where
+    D: de::Deserializer<'de>,
+    T: de::Deserialize<'de>,
+{
+    let profiler = Arc::new(profile::Profiler::new());
+    let wrapper = Deserializer::with_sink(deserializer, profiler.clone()).scoped_to::<T>();
+    let result = T::deserialize(wrapper);
+    (result, profiler.finish())
+}
+
+/// Serializes `value`, logging every call at the trace level.
+///
+/// When trace logging is disabled this is the same as calling `value.serialize` directly.
+#[inline]
+pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized + serde::Serialize,
+    S: serde::Serializer,
+{
+    if !enabled(log::Level::Trace) {
+        return value.serialize(serializer);
+    }
+    value.serialize(Serializer::new(serializer).scoped_to::<T>())
 }
 
 pub struct Deserializer;
 
 impl Deserializer {
+    #[allow(clippy::new_ret_no_self)]
     #[inline]
     pub fn new<'de, D>(deserializer: D) -> Wrapper<D>
     where
@@ -20,17 +175,76 @@ impl Deserializer {
     {
         Wrapper::new(deserializer)
     }
+
+    #[inline]
+    pub fn with_sink<'de, D, S>(deserializer: D, sink: S) -> Wrapper<D>
+    where
+        D: de::Deserializer<'de>,
+        S: Sink + 'static,
+    {
+        Wrapper::with_context(deserializer, Arc::new(Context::new(Arc::new(sink))))
+    }
+}
+
+pub struct Serializer;
+
+impl Serializer {
+    #[allow(clippy::new_ret_no_self)]
+    #[inline]
+    pub fn new<S>(serializer: S) -> Wrapper<S>
+    where
+        S: serde::Serializer,
+    {
+        Wrapper::new(serializer)
+    }
+
+    #[inline]
+    pub fn with_sink<T, S>(serializer: T, sink: S) -> Wrapper<T>
+    where
+        T: serde::Serializer,
+        S: Sink + 'static,
+    {
+        Wrapper::with_context(serializer, Arc::new(Context::new(Arc::new(sink))))
+    }
 }
 
 pub struct Wrapper<A> {
     inner: A,
     depth: u32,
+    path: Path,
+    ctx: Arc<Context>,
+    index: usize,
+    key: Option<String>,
+    scope: Option<Arc<str>>,
+    redacted: bool,
+    sample: Option<Box<Sample>>,
 }
 
 impl<A> Wrapper<A> {
     #[inline]
     pub(crate) fn new(inner: A) -> Self {
-        Self { inner, depth: 0 }
+        Self::with_context(inner, Default::default())
+    }
+
+    #[inline]
+    pub(crate) fn with_context(inner: A, ctx: Arc<Context>) -> Self {
+        let path = Path::root();
+        Self {
+            inner,
+            depth: 0,
+            redacted: ctx.redacts(&path),
+            path,
+            ctx,
+            index: 0,
+            key: None,
+            scope: None,
+            sample: None,
+        }
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
     }
 
     #[inline]
@@ -38,12 +252,171 @@ impl<A> Wrapper<A> {
         Wrapper {
             inner,
             depth: self.depth + 1,
+            path: self.path.clone(),
+            ctx: self.ctx.clone(),
+            index: 0,
+            key: None,
+            scope: self.scope.clone(),
+            redacted: self.redacted,
+            sample: None,
+        }
+    }
+
+    #[inline]
+    fn sub_wrap_at<B>(&self, inner: B, segment: Segment) -> Wrapper<B> {
+        self.sub_wrap(inner).push(segment)
+    }
+
+    #[inline]
+    fn sub_wrap_index<B>(&mut self, inner: B) -> Wrapper<B> {
+        let index = self.index;
+        self.index += 1;
+        self.sub_wrap_at(inner, Segment::Index(index))
+    }
+
+    #[inline]
+    fn sub_wrap_value<B>(&mut self, inner: B) -> Wrapper<B> {
+        match self.key.take() {
+            Some(key) => {
+                self.index += 1;
+                self.sub_wrap_at(inner, Segment::Key(key.into()))
+            }
+            None => self.sub_wrap_index(inner),
         }
     }
 
+    #[cfg(not(feature = "disabled"))]
     #[inline]
-    fn unwrap(self) -> A {
-        self.inner
+    fn sub_map<B, E, F>(self, f: F) -> Result<Wrapper<B>, E>
+    where
+        F: FnOnce(A) -> Result<B, E>,
+    {
+        let wrapper = self.sub_wrap(());
+        f(self.inner).map(|inner| wrapper.rewrap(inner))
+    }
+
+    #[inline]
+    fn rewrap<B>(self, inner: B) -> Wrapper<B> {
+        Wrapper {
+            inner,
+            depth: self.depth,
+            path: self.path,
+            ctx: self.ctx,
+            index: 0,
+            key: None,
+            scope: self.scope,
+            redacted: self.redacted,
+            sample: None,
+        }
+    }
+
+    #[inline]
+    fn push(mut self, segment: Segment) -> Self {
+        self.path = self.path.join(segment);
+        self.redacted = self.redacted || self.ctx.redacts(&self.path);
+        self
+    }
+
+    #[inline]
+    fn redact<'a>(&self, value: Option<Value<'a>>) -> Option<Value<'a>> {
+        match value {
+            Some(value) if self.redacted => Some(self.ctx.redact(&value)),
+            value => value,
+        }
+    }
+
+    /// Scopes the events to the struct or enum `name`, until a nested one replaces it.
+    #[cfg(not(feature = "disabled"))]
+    fn enter_scope(&mut self, name: &str) {
+        self.scope = Some(name.into());
+    }
+
+    /// Scopes the events to the type being (de)serialized at the top level, e.g. `Vec` for a
+    /// `Vec<u32>`, until a struct or enum replaces it.
+    pub(crate) fn scoped_to<T>(mut self) -> Self
+    where
+        T: ?Sized,
+    {
+        let name = type_name::<T>();
+        let name = name.split('<').next().unwrap_or(name);
+        let name = name.rsplit("::").next().unwrap_or(name);
+        if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            self.scope = Some(name.into());
+        }
+        self
+    }
+
+    #[inline]
+    fn capture<F>(&self, f: F)
+    where
+        F: FnOnce() -> String,
+    {
+        self.ctx.capture(f);
+    }
+
+    #[inline]
+    fn arm_capture(&self) {
+        self.ctx.arm_capture();
+    }
+
+    #[inline]
+    fn take_capture(&self) -> Option<String> {
+        self.ctx.take_capture()
+    }
+
+    #[cfg(not(feature = "disabled"))]
+    #[inline]
+    fn span(&self, method: &'static str) -> Span {
+        Span::enter(method, self.depth, &self.path)
+    }
+
+    fn emit(
+        &self,
+        kind: Kind,
+        method: &'static str,
+        args: &[(&str, Value<'_>)],
+        value: Option<Value<'_>>,
+    ) {
+        let value = self.redact(value);
+        self.ctx.emit(&Event {
+            kind,
+            method,
+            type_name: None,
+            scope: self.scope.as_deref(),
+            depth: self.depth,
+            path: &self.path,
+            args,
+            value: value.as_ref(),
+            outcome: None,
+            elapsed: None,
+            enter: false,
+        });
+    }
+
+    /// Emits the enter event of a call, the exit event is emitted by [`Call::exit`].
+    fn enter(
+        &self,
+        kind: Kind,
+        method: &'static str,
+        type_name: Option<&'static str>,
+        args: &[(&str, Value<'_>)],
+        value: Option<Value<'_>>,
+    ) -> Call {
+        let value = self.redact(value);
+        self.ctx.emit(&Event {
+            kind,
+            method,
+            type_name,
+            scope: self.scope.as_deref(),
+            depth: self.depth,
+            path: &self.path,
+            args,
+            value: value.as_ref(),
+            outcome: None,
+            elapsed: None,
+            enter: true,
+        });
+        Call::new(self, kind, method, type_name)
     }
 }
 
@@ -64,12 +437,25 @@ where
         Self {
             inner: self.inner.clone(),
             depth: self.depth,
+            path: self.path.clone(),
+            ctx: self.ctx.clone(),
+            index: self.index,
+            key: self.key.clone(),
+            scope: self.scope.clone(),
+            redacted: self.redacted,
+            sample: None,
         }
     }
 
     fn clone_from(&mut self, source: &Self) {
         self.inner = source.inner.clone();
         self.depth = source.depth;
+        self.path = source.path.clone();
+        self.ctx = source.ctx.clone();
+        self.index = source.index;
+        self.key = source.key.clone();
+        self.scope = source.scope.clone();
+        self.redacted = source.redacted;
     }
 }
 
@@ -81,6 +467,7 @@ where
         f.debug_struct("Wrapper")
             .field("inner", &self.inner)
             .field("depth", &self.depth)
+            .field("path", &self.path)
             .finish()
     }
 }
@@ -105,6 +492,7 @@ impl<T> From<T> for Wrapper<T> {
     }
 }
 
+#[cfg(not(feature = "disabled"))]
 impl<'de, T> de::Deserialize<'de> for Wrapper<T>
 where
     T: de::Deserialize<'de>,
@@ -113,11 +501,34 @@ where
     where
         D: de::Deserializer<'de>,
     {
-        log::trace!("Deserialize: deserialize");
-        T::deserialize(Wrapper::new(deserializer)).map(Wrapper::new)
+        let deserializer = Wrapper::new(deserializer);
+        let call = deserializer.enter(
+            Kind::Deserialize,
+            "deserialize",
+            Some(type_name::<T>()),
+            &[],
+            None,
+        );
+        call.exit(T::deserialize(deserializer).map(Wrapper::new))
+    }
+
+    fn deserialize_in_place<D>(deserializer: D, place: &mut Self) -> Result<(), D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let deserializer = Wrapper::new(deserializer);
+        let call = deserializer.enter(
+            Kind::Deserialize,
+            "deserialize_in_place",
+            Some(type_name::<T>()),
+            &[],
+            None,
+        );
+        call.exit(T::deserialize_in_place(deserializer, &mut place.inner))
     }
 }
 
+#[cfg(not(feature = "disabled"))]
 impl<'de, D> de::Deserializer<'de> for Wrapper<D>
 where
     D: de::Deserializer<'de>,
@@ -128,241 +539,414 @@ where
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_any");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_any",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_any");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_any(visitor)
+        call.exit(self.inner.deserialize_any(visitor))
     }
 
     fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_bool");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_bool",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_bool");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_bool(visitor)
+        call.exit(self.inner.deserialize_bool(visitor))
     }
 
     fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_i8");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_i8",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_i8");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_i8(visitor)
+        call.exit(self.inner.deserialize_i8(visitor))
     }
 
     fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_i16");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_i16",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_i16");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_i16(visitor)
+        call.exit(self.inner.deserialize_i16(visitor))
     }
 
     fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_i32");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_i32",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_i32");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_i32(visitor)
+        call.exit(self.inner.deserialize_i32(visitor))
     }
 
     fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_i64");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_i64",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_i64");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_i64(visitor)
+        call.exit(self.inner.deserialize_i64(visitor))
     }
 
-    serde::serde_if_integer128! {
-        fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
-        where
-            V: de::Visitor<'de>,
-        {
-            log::trace!("Deserializer: deserialize_i128");
-            let visitor = self.sub_wrap(visitor);
-            self.inner.deserialize_i128(visitor)
-        }
+    fn deserialize_i128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_i128",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_i128");
+        let visitor = self.sub_wrap(visitor);
+        call.exit(self.inner.deserialize_i128(visitor))
     }
 
     fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_u8");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_u8",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_u8");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_u8(visitor)
+        call.exit(self.inner.deserialize_u8(visitor))
     }
 
     fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_u16");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_u16",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_u16");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_u16(visitor)
+        call.exit(self.inner.deserialize_u16(visitor))
     }
 
     fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_u32");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_u32",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_u32");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_u32(visitor)
+        call.exit(self.inner.deserialize_u32(visitor))
     }
 
     fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_u64");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_u64",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_u64");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_u64(visitor)
+        call.exit(self.inner.deserialize_u64(visitor))
     }
 
-    serde::serde_if_integer128! {
-        fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
-        where
-            V: de::Visitor<'de>,
-        {
-            log::trace!("Deserializer: deserialize_u128");
-            let visitor = self.sub_wrap(visitor);
-            self.inner.deserialize_u128(visitor)
-        }
+    fn deserialize_u128<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_u128",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_u128");
+        let visitor = self.sub_wrap(visitor);
+        call.exit(self.inner.deserialize_u128(visitor))
     }
 
     fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_f32");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_f32",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_f32");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_f32(visitor)
+        call.exit(self.inner.deserialize_f32(visitor))
     }
 
     fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_f64");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_f64",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_f64");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_f64(visitor)
+        call.exit(self.inner.deserialize_f64(visitor))
     }
 
     fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_char");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_char",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_char");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_char(visitor)
+        call.exit(self.inner.deserialize_char(visitor))
     }
 
     fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_str");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_str",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_str");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_str(visitor)
+        call.exit(self.inner.deserialize_str(visitor))
     }
 
     fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_string");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_string",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_string");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_string(visitor)
+        call.exit(self.inner.deserialize_string(visitor))
     }
 
     fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_bytes");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_bytes",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_bytes");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_bytes(visitor)
+        call.exit(self.inner.deserialize_bytes(visitor))
     }
 
     fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_byte_buf");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_byte_buf",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_byte_buf");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_byte_buf(visitor)
+        call.exit(self.inner.deserialize_byte_buf(visitor))
     }
 
     fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_option");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_option",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_option");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_option(visitor)
+        call.exit(self.inner.deserialize_option(visitor))
     }
 
     fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_unit");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_unit",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_unit");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_unit(visitor)
+        call.exit(self.inner.deserialize_unit(visitor))
     }
 
     fn deserialize_unit_struct<V>(
-        self,
+        mut self,
         name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_unit_struct");
+        self.enter_scope(name);
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_unit_struct",
+            Some(type_name::<V::Value>()),
+            &[("name", name.into())],
+            None,
+        );
+        let _span = self.span("deserialize_unit_struct").name(name);
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_unit_struct(name, visitor)
+        call.exit(self.inner.deserialize_unit_struct(name, visitor))
     }
 
     fn deserialize_newtype_struct<V>(
-        self,
+        mut self,
         name: &'static str,
         visitor: V,
     ) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_newtype_struct");
+        self.enter_scope(name);
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_newtype_struct",
+            Some(type_name::<V::Value>()),
+            &[("name", name.into())],
+            None,
+        );
+        let _span = self.span("deserialize_newtype_struct").name(name);
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_newtype_struct(name, visitor)
+        call.exit(self.inner.deserialize_newtype_struct(name, visitor))
     }
 
     fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_seq");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_seq",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_seq");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_seq(visitor)
+        call.exit(self.inner.deserialize_seq(visitor))
     }
 
     fn deserialize_tuple<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_tuple");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_tuple",
+            Some(type_name::<V::Value>()),
+            &[("len", len.into())],
+            None,
+        );
+        let _span = self.span("deserialize_tuple").len(len);
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_tuple(len, visitor)
+        call.exit(self.inner.deserialize_tuple(len, visitor))
     }
 
     fn deserialize_tuple_struct<V>(
-        self,
+        mut self,
         name: &'static str,
         len: usize,
         visitor: V,
@@ -370,22 +954,37 @@ where
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_tuple_struct");
+        self.enter_scope(name);
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_tuple_struct",
+            Some(type_name::<V::Value>()),
+            &[("name", name.into()), ("len", len.into())],
+            None,
+        );
+        let _span = self.span("deserialize_tuple_struct").name(name).len(len);
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_tuple_struct(name, len, visitor)
+        call.exit(self.inner.deserialize_tuple_struct(name, len, visitor))
     }
 
     fn deserialize_map<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_map");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_map",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_map");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_map(visitor)
+        call.exit(self.inner.deserialize_map(visitor))
     }
 
     fn deserialize_struct<V>(
-        self,
+        mut self,
         name: &'static str,
         fields: &'static [&'static str],
         visitor: V,
@@ -393,13 +992,21 @@ where
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_struct");
+        self.enter_scope(name);
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_struct",
+            Some(type_name::<V::Value>()),
+            &[("name", name.into()), ("fields", fields.into())],
+            None,
+        );
+        let _span = self.span("deserialize_struct").name(name).fields(fields);
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_struct(name, fields, visitor)
+        call.exit(self.inner.deserialize_struct(name, fields, visitor))
     }
 
     fn deserialize_enum<V>(
-        self,
+        mut self,
         name: &'static str,
         variants: &'static [&'static str],
         visitor: V,
@@ -407,27 +1014,60 @@ where
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_enum");
+        self.enter_scope(name);
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_enum",
+            Some(type_name::<V::Value>()),
+            &[("name", name.into()), ("variants", variants.into())],
+            None,
+        );
+        let _span = self.span("deserialize_enum").name(name).variants(variants);
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_enum(name, variants, visitor)
+        call.exit(self.inner.deserialize_enum(name, variants, visitor))
     }
 
     fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_identifier");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_identifier",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_identifier");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_identifier(visitor)
+        call.exit(self.inner.deserialize_identifier(visitor))
     }
 
     fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
     where
         V: de::Visitor<'de>,
     {
-        log::trace!("Deserializer: deserialize_ignored_any");
+        let call = self.enter(
+            Kind::Deserializer,
+            "deserialize_ignored_any",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let _span = self.span("deserialize_ignored_any");
         let visitor = self.sub_wrap(visitor);
-        self.inner.deserialize_ignored_any(visitor)
+        call.exit(self.inner.deserialize_ignored_any(visitor))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        let human_readable = self.inner.is_human_readable();
+        self.emit(
+            Kind::Deserializer,
+            "is_human_readable",
+            &[],
+            Some(human_readable.into()),
+        );
+        human_readable
     }
 }
 
@@ -445,225 +1085,403 @@ where
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_bool({:?})", v);
-        self.inner.visit_bool(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_bool",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_bool(v))
     }
 
     fn visit_i8<E>(self, v: i8) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_i8({:?})", v);
-        self.inner.visit_i8(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_i8",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_i8(v))
     }
 
     fn visit_i16<E>(self, v: i16) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_i16({:?})", v);
-        self.inner.visit_i16(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_i16",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_i16(v))
     }
 
     fn visit_i32<E>(self, v: i32) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_i32({:?})", v);
-        self.inner.visit_i32(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_i32",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_i32(v))
     }
 
     fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_i64({:?})", v);
-        self.inner.visit_i64(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_i64",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_i64(v))
     }
 
-    serde::serde_if_integer128! {
-        fn visit_i128<E>(self, v: i128) -> Result<Self::Value,E>
-        where
-            E: de::Error,
-        {
-            log::trace!("Visitor: visit_i128({:?})", v);
-            self.inner.visit_i128(v)
-        }
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_i128",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_i128(v))
     }
 
     fn visit_u8<E>(self, v: u8) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_u8({:?})", v);
-        self.inner.visit_u8(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_u8",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_u8(v))
     }
 
     fn visit_u16<E>(self, v: u16) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_u16({:?})", v);
-        self.inner.visit_u16(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_u16",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_u16(v))
     }
 
     fn visit_u32<E>(self, v: u32) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_u32({:?})", v);
-        self.inner.visit_u32(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_u32",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_u32(v))
     }
 
     fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_u64({:?})", v);
-        self.inner.visit_u64(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_u64",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_u64(v))
     }
 
-    serde::serde_if_integer128! {
-        fn visit_u128<E>(self, v: u128) -> Result<Self::Value,E>
-        where
-            E: de::Error,
-        {
-            log::trace!("Visitor: visit_u128({:?})", v);
-            self.inner.visit_u128(v)
-        }
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_u128",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_u128(v))
     }
 
     fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_f32({:?})", v);
-        self.inner.visit_f32(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_f32",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_f32(v))
     }
 
     fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_f64({:?})", v);
-        self.inner.visit_f64(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_f64",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_f64(v))
     }
 
     fn visit_char<E>(self, v: char) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_char({:?})", v);
-        self.inner.visit_char(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_char",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_string());
+        call.exit(self.inner.visit_char(v))
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_str({:?})", v);
-        self.inner.visit_str(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_str",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_owned());
+        call.exit(self.inner.visit_str(v))
     }
 
     fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_borrowed_str({:?})", v);
-        self.inner.visit_borrowed_str(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_borrowed_str",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| v.to_owned());
+        call.exit(self.inner.visit_borrowed_str(v))
     }
 
     fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_string({:?})", v);
-        self.inner.visit_string(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_string",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.as_str().into()),
+        );
+        self.capture(|| v.clone());
+        call.exit(self.inner.visit_string(v))
     }
 
     fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_bytes({:?})", v);
-        self.inner.visit_bytes(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_bytes",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| String::from_utf8_lossy(v).into_owned());
+        call.exit(self.inner.visit_bytes(v))
     }
 
     fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_borrowed_bytes({:?})", v);
-        self.inner.visit_borrowed_bytes(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_borrowed_bytes",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.into()),
+        );
+        self.capture(|| String::from_utf8_lossy(v).into_owned());
+        call.exit(self.inner.visit_borrowed_bytes(v))
     }
 
     fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_byte_buf({:?})", v);
-        self.inner.visit_byte_buf(v)
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_byte_buf",
+            Some(type_name::<V::Value>()),
+            &[],
+            Some(v.as_slice().into()),
+        );
+        self.capture(|| String::from_utf8_lossy(&v).into_owned());
+        call.exit(self.inner.visit_byte_buf(v))
     }
 
     fn visit_none<E>(self) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_none()");
-        self.inner.visit_none()
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_none",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        call.exit(self.inner.visit_none())
     }
 
     fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
         D: de::Deserializer<'de>,
     {
-        log::trace!("Visitor: visit_some(...)");
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_some",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
         let deserializer = self.sub_wrap(deserializer);
-        self.inner.visit_some(deserializer)
+        call.exit(self.inner.visit_some(deserializer))
     }
 
     fn visit_unit<E>(self) -> Result<Self::Value, E>
     where
         E: de::Error,
     {
-        log::trace!("Visitor: visit_unit()");
-        self.inner.visit_unit()
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_unit",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        call.exit(self.inner.visit_unit())
     }
 
     fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
         D: de::Deserializer<'de>,
     {
-        log::trace!("Visitor: visit_newtype_struct(...)");
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_newtype_struct",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
         let deserializer = self.sub_wrap(deserializer);
-        self.inner.visit_newtype_struct(deserializer)
+        call.exit(self.inner.visit_newtype_struct(deserializer))
     }
 
     fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
     where
         A: de::SeqAccess<'de>,
     {
-        log::trace!("Visitor: visit_seq(...)");
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_seq",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
         let seq = self.sub_wrap(seq);
-        self.inner.visit_seq(seq)
+        call.exit(self.inner.visit_seq(seq))
     }
 
     fn visit_map<A>(self, map: A) -> Result<Self::Value, A::Error>
     where
         A: de::MapAccess<'de>,
     {
-        log::trace!("Visitor: visit_map(...)");
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_map",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
         let map = self.sub_wrap(map);
-        self.inner.visit_map(map)
+        call.exit(self.inner.visit_map(map))
     }
 
     fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
     where
         A: de::EnumAccess<'de>,
     {
-        log::trace!("Visitor: visit_enum(...)");
+        let call = self.enter(
+            Kind::Visitor,
+            "visit_enum",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
         let data = self.sub_wrap(data);
-        self.inner.visit_enum(data)
+        call.exit(self.inner.visit_enum(data))
     }
 }
 
@@ -677,9 +1495,15 @@ where
     where
         D: de::Deserializer<'de>,
     {
-        log::trace!("DeserializeSeed: deserialize");
+        let call = self.enter(
+            Kind::DeserializeSeed,
+            "deserialize",
+            Some(type_name::<S::Value>()),
+            &[],
+            None,
+        );
         let deserializer = self.sub_wrap(deserializer);
-        self.inner.deserialize(deserializer)
+        call.exit(self.inner.deserialize(deserializer))
     }
 }
 
@@ -693,19 +1517,42 @@ where
     where
         T: de::DeserializeSeed<'de>,
     {
-        log::trace!("SeqAccess: next_element_seed");
-        let seed = self.sub_wrap(seed);
-        self.inner.next_element_seed(seed)
+        self.begin_element(Kind::SeqAccess);
+        let call = self.enter(
+            Kind::SeqAccess,
+            "next_element_seed",
+            Some(type_name::<T::Value>()),
+            &[],
+            None,
+        );
+        let seed = self.sub_wrap_index(seed);
+        let element = call.exit(self.inner.next_element_seed(seed));
+        match element {
+            Ok(Some(_)) => self.end_element(),
+            _ => self.end_collection(),
+        }
+        element
     }
 
     fn next_element<T>(&mut self) -> Result<Option<T>, Self::Error>
     where
         T: de::Deserialize<'de>,
     {
-        log::trace!("SeqAccess: next_element");
-        self.inner
-            .next_element::<Wrapper<T>>()
-            .map(|v| v.map(Wrapper::unwrap))
+        self.begin_element(Kind::SeqAccess);
+        let call = self.enter(
+            Kind::SeqAccess,
+            "next_element",
+            Some(type_name::<T>()),
+            &[],
+            None,
+        );
+        let seed = self.sub_wrap_index(PhantomData);
+        let element = call.exit(self.inner.next_element_seed(seed));
+        match element {
+            Ok(Some(_)) => self.end_element(),
+            _ => self.end_collection(),
+        }
+        element
     }
 
     fn size_hint(&self) -> Option<usize> {
@@ -713,6 +1560,30 @@ where
     }
 }
 
+impl<'de, A> Wrapper<A>
+where
+    A: de::MapAccess<'de>,
+{
+    fn next_key_inner<K>(&mut self, seed: K) -> Result<Option<K::Value>, A::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        let seed = self.sub_wrap(seed);
+        self.arm_capture();
+        let key = self.inner.next_key_seed(seed);
+        self.key = self.take_capture();
+        key
+    }
+
+    fn next_value_inner<V>(&mut self, seed: V) -> Result<V::Value, A::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let seed = self.sub_wrap_value(seed);
+        self.inner.next_value_seed(seed)
+    }
+}
+
 impl<'de, A> de::MapAccess<'de> for Wrapper<A>
 where
     A: de::MapAccess<'de>,
@@ -723,18 +1594,38 @@ where
     where
         K: de::DeserializeSeed<'de>,
     {
-        log::trace!("MapAccess: next_key_seed");
-        let seed = self.sub_wrap(seed);
-        self.inner.next_key_seed(seed)
+        self.begin_element(Kind::MapAccess);
+        let call = self.enter(
+            Kind::MapAccess,
+            "next_key_seed",
+            Some(type_name::<K::Value>()),
+            &[],
+            None,
+        );
+        let key = call.exit(self.next_key_inner(seed));
+        if !matches!(key, Ok(Some(_))) {
+            self.end_collection();
+        }
+        key
     }
 
     fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
     where
         V: de::DeserializeSeed<'de>,
     {
-        log::trace!("MapAccess: next_value_seed");
-        let seed = self.sub_wrap(seed);
-        self.inner.next_value_seed(seed)
+        let call = self.enter(
+            Kind::MapAccess,
+            "next_value_seed",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        let value = call.exit(self.next_value_inner(seed));
+        match value {
+            Ok(_) => self.end_element(),
+            Err(_) => self.end_collection(),
+        }
+        value
     }
 
     fn next_entry_seed<K, V>(
@@ -746,28 +1637,61 @@ where
         K: de::DeserializeSeed<'de>,
         V: de::DeserializeSeed<'de>,
     {
-        log::trace!("MapAccess: next_entry_seed");
-        let kseed = self.sub_wrap(kseed);
-        let vseed = self.sub_wrap(vseed);
-        self.inner.next_entry_seed(kseed, vseed)
+        self.begin_element(Kind::MapAccess);
+        let call = self.enter(
+            Kind::MapAccess,
+            "next_entry_seed",
+            Some(type_name::<(K::Value, V::Value)>()),
+            &[],
+            None,
+        );
+        let entry = call.exit(self.next_key_inner(kseed).and_then(|key| match key {
+            Some(key) => self.next_value_inner(vseed).map(|value| Some((key, value))),
+            None => Ok(None),
+        }));
+        match entry {
+            Ok(Some(_)) => self.end_element(),
+            _ => self.end_collection(),
+        }
+        entry
     }
 
     fn next_key<K>(&mut self) -> Result<Option<K>, Self::Error>
     where
         K: de::Deserialize<'de>,
     {
-        log::trace!("MapAccess: next_key");
-        self.inner
-            .next_key::<Wrapper<K>>()
-            .map(|v| v.map(Wrapper::unwrap))
+        self.begin_element(Kind::MapAccess);
+        let call = self.enter(
+            Kind::MapAccess,
+            "next_key",
+            Some(type_name::<K>()),
+            &[],
+            None,
+        );
+        let key = call.exit(self.next_key_inner(PhantomData));
+        if !matches!(key, Ok(Some(_))) {
+            self.end_collection();
+        }
+        key
     }
 
     fn next_value<V>(&mut self) -> Result<V, Self::Error>
     where
         V: de::Deserialize<'de>,
     {
-        log::trace!("MapAccess: next_value");
-        self.inner.next_value::<Wrapper<V>>().map(Wrapper::unwrap)
+        let call = self.enter(
+            Kind::MapAccess,
+            "next_value",
+            Some(type_name::<V>()),
+            &[],
+            None,
+        );
+        let value = call.exit(self.next_value_inner(PhantomData));
+        match value {
+            Ok(_) => self.end_element(),
+            Err(_) => self.end_collection(),
+        }
+        value
     }
 
     fn next_entry<K, V>(&mut self) -> Result<Option<(K, V)>, Self::Error>
@@ -775,10 +1699,27 @@ where
         K: de::Deserialize<'de>,
         V: de::Deserialize<'de>,
     {
-        log::trace!("MapAccess: next_entry");
-        self.inner
-            .next_entry::<Wrapper<K>, Wrapper<V>>()
-            .map(|r| r.map(|(k, v)| (k.unwrap(), v.unwrap())))
+        self.begin_element(Kind::MapAccess);
+        let call = self.enter(
+            Kind::MapAccess,
+            "next_entry",
+            Some(type_name::<(K, V)>()),
+            &[],
+            None,
+        );
+        let entry = call.exit(self.next_key_inner(PhantomData).and_then(|key| {
+            match key {
+                Some(key) => self
+                    .next_value_inner(PhantomData)
+                    .map(|value| Some((key, value))),
+                None => Ok(None),
+            }
+        }));
+        match entry {
+            Ok(Some(_)) => self.end_element(),
+            _ => self.end_collection(),
+        }
+        entry
     }
 
     fn size_hint(&self) -> Option<usize> {
@@ -786,29 +1727,178 @@ where
     }
 }
 
+impl<'de, A> Wrapper<A>
+where
+    A: de::EnumAccess<'de>,
+{
+    #[allow(clippy::type_complexity)]
+    fn variant_inner<V>(self, seed: V) -> Result<(V::Value, Wrapper<A::Variant>), A::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let seed = self.sub_wrap(seed);
+        let variant = self.sub_wrap(());
+        self.arm_capture();
+        let (value, inner) = self.inner.variant_seed(seed)?;
+        let variant = match variant.take_capture() {
+            Some(name) => variant.push(Segment::Variant(name.into())),
+            None => variant,
+        };
+        Ok((value, variant.rewrap(inner)))
+    }
+}
+
 impl<'de, A> de::EnumAccess<'de> for Wrapper<A>
 where
     A: de::EnumAccess<'de>,
 {
     type Error = A::Error;
-    type Variant = A::Variant;
+    type Variant = Wrapper<A::Variant>;
 
     fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
     where
         V: de::DeserializeSeed<'de>,
     {
-        log::trace!("EnumAccess: variant_seed");
-        let seed = self.sub_wrap(seed);
-        self.inner.variant_seed(seed)
+        let call = self.enter(
+            Kind::EnumAccess,
+            "variant_seed",
+            Some(type_name::<V::Value>()),
+            &[],
+            None,
+        );
+        call.exit(self.variant_inner(seed))
     }
 
     fn variant<V>(self) -> Result<(V, Self::Variant), Self::Error>
     where
         V: de::Deserialize<'de>,
     {
-        log::trace!("EnumAccess: variant");
-        self.inner
-            .variant::<Wrapper<V>>()
-            .map(|(k, v)| (k.unwrap(), v))
+        let call = self.enter(
+            Kind::EnumAccess,
+            "variant",
+            Some(type_name::<V>()),
+            &[],
+            None,
+        );
+        call.exit(self.variant_inner(PhantomData))
+    }
+}
+
+impl<'de, A> de::VariantAccess<'de> for Wrapper<A>
+where
+    A: de::VariantAccess<'de>,
+{
+    type Error = A::Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        let call = self.enter(Kind::VariantAccess, "unit_variant", None, &[], None);
+        call.exit(self.inner.unit_variant())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        let call = self.enter(
+            Kind::VariantAccess,
+            "newtype_variant_seed",
+            Some(type_name::<T::Value>()),
+            &[],
+            None,
+        );
+        let seed = self.sub_wrap(seed);
+        call.exit(self.inner.newtype_variant_seed(seed))
+    }
+
+    fn newtype_variant<T>(self) -> Result<T, Self::Error>
+    where
+        T: de::Deserialize<'de>,
+    {
+        let call = self.enter(
+            Kind::VariantAccess,
+            "newtype_variant",
+            Some(type_name::<T>()),
+            &[],
+            None,
+        );
+        let seed = self.sub_wrap(PhantomData);
+        call.exit(self.inner.newtype_variant_seed(seed))
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let call = self.enter(
+            Kind::VariantAccess,
+            "tuple_variant",
+            Some(type_name::<V::Value>()),
+            &[("len", len.into())],
+            None,
+        );
+        let visitor = self.sub_wrap(visitor);
+        call.exit(self.inner.tuple_variant(len, visitor))
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let call = self.enter(
+            Kind::VariantAccess,
+            "struct_variant",
+            Some(type_name::<V::Value>()),
+            &[("fields", fields.into())],
+            None,
+        );
+        let visitor = self.sub_wrap(visitor);
+        call.exit(self.inner.struct_variant(fields, visitor))
+    }
+}
+
+#[cfg(all(test, not(feature = "disabled")))]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    struct Capture(Mutex<Vec<String>>);
+
+    impl log::Log for Capture {
+        fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record<'_>) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+    #[test]
+    fn wrapper_deserialize_in_place() {
+        log::set_logger(&LOGGER).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        let mut place = Wrapper::new(Vec::<u32>::with_capacity(16));
+        place.inner.extend([7, 8, 9, 10]);
+        let (ptr, capacity) = (place.inner.as_ptr(), place.inner.capacity());
+
+        let mut json = serde_json::Deserializer::from_str("[1, 2, 3]");
+        de::Deserialize::deserialize_in_place(&mut json, &mut place).unwrap();
+        assert_eq!(place.inner, [1, 2, 3]);
+        assert_eq!(place.inner.as_ptr(), ptr);
+        assert_eq!(place.inner.capacity(), capacity);
+
+        // Other tests may log at the same time.
+        let lines = LOGGER.0.lock().unwrap();
+        let event = "Deserialize: deserialize_in_place -> alloc::vec::Vec<u32>";
+        assert!(lines.iter().any(|l| l.starts_with(event)), "{:#?}", lines);
     }
 }
@@ -0,0 +1,170 @@
+use crate::event::Identifier;
+use serde::{de, ser, Deserialize, Serialize};
+use std::{borrow::Cow, fmt, sync::Arc};
+
+/// A location in the document, rendered as a JSON pointer (e.g. `/servers/3/tls`).
+///
+/// The root is rendered as `/`.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct Path(Option<Arc<Node>>);
+
+#[derive(PartialEq, Eq, Hash)]
+struct Node {
+    parent: Path,
+    segment: Segment,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Segment {
+    Key(Cow<'static, str>),
+    Index(usize),
+    Variant(Cow<'static, str>),
+}
+
+impl Path {
+    #[inline]
+    pub fn root() -> Self {
+        Self(None)
+    }
+
+    #[inline]
+    pub fn is_root(&self) -> bool {
+        self.0.is_none()
+    }
+
+    pub fn join(&self, segment: Segment) -> Self {
+        Self(Some(Arc::new(Node {
+            parent: self.clone(),
+            segment,
+        })))
+    }
+
+    pub fn parent(&self) -> Option<&Path> {
+        self.0.as_ref().map(|n| &n.parent)
+    }
+
+    pub fn last(&self) -> Option<&Segment> {
+        self.0.as_ref().map(|n| &n.segment)
+    }
+
+    pub fn segments(&self) -> Vec<&Segment> {
+        let mut segments = Vec::new();
+        let mut path = self;
+        while let Some(node) = &path.0 {
+            segments.push(&node.segment);
+            path = &node.parent;
+        }
+        segments.reverse();
+        segments
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_root() {
+            return f.write_str("/");
+        }
+        for segment in self.segments() {
+            write!(f, "/{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Path({:?})", self.to_string())
+    }
+}
+
+/// Paths are (de)serialized as the sequence of their segments.
+impl Serialize for Path {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        serializer.collect_seq(self.segments())
+    }
+}
+
+impl<'de> Deserialize<'de> for Path {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        let segments = Vec::<Segment>::deserialize(deserializer)?;
+        Ok(segments
+            .into_iter()
+            .fold(Path::root(), |path, segment| path.join(segment)))
+    }
+}
+
+const SEGMENT_VARIANTS: &[&str] = &["Key", "Index", "Variant"];
+
+/// Segments are (de)serialized as newtype variants.
+impl Serialize for Segment {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match self {
+            Segment::Key(k) => serializer.serialize_newtype_variant("Segment", 0, "Key", &**k),
+            Segment::Index(i) => serializer.serialize_newtype_variant("Segment", 1, "Index", i),
+            Segment::Variant(v) => {
+                serializer.serialize_newtype_variant("Segment", 2, "Variant", &**v)
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Segment {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct SegmentVisitor;
+
+        impl<'de> de::Visitor<'de> for SegmentVisitor {
+            type Value = Segment;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a path segment")
+            }
+
+            fn visit_enum<A>(self, data: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::EnumAccess<'de>,
+            {
+                use de::VariantAccess as _;
+
+                Ok(
+                    match data.variant_seed(Identifier::variant(SEGMENT_VARIANTS))? {
+                        (0, v) => Segment::Key(Cow::Owned(v.newtype_variant()?)),
+                        (1, v) => Segment::Index(v.newtype_variant()?),
+                        (_, v) => Segment::Variant(Cow::Owned(v.newtype_variant()?)),
+                    },
+                )
+            }
+        }
+
+        deserializer.deserialize_enum("Segment", SEGMENT_VARIANTS, SegmentVisitor)
+    }
+}
+
+impl fmt::Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::Key(s) | Segment::Variant(s) => {
+                for c in s.chars() {
+                    match c {
+                        '~' => f.write_str("~0")?,
+                        '/' => f.write_str("~1")?,
+                        c => fmt::Write::write_char(f, c)?,
+                    }
+                }
+                Ok(())
+            }
+            Segment::Index(i) => write!(f, "{}", i),
+        }
+    }
+}
@@ -0,0 +1,393 @@
+use crate::{sink::Sink, Event, Kind, Path};
+use std::{cmp::Reverse, collections::HashMap, fmt, hash::Hash, sync::Mutex, time::Duration};
+
+/// Where the time went in a deserialization, per path and per type, see
+/// [`profile`](crate::profile).
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    paths: Vec<(Path, Timing)>,
+    types: Vec<(String, Timing)>,
+}
+
+/// The time spent in the calls at a path or producing a type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Timing {
+    pub calls: usize,
+    /// Including the nested paths or types.
+    pub total: Duration,
+    /// Excluding the nested paths or types.
+    pub self_time: Duration,
+}
+
+impl Profile {
+    /// The paths visited, the most expensive subtree first.
+    #[inline]
+    pub fn paths(&self) -> &[(Path, Timing)] {
+        &self.paths
+    }
+
+    /// The types produced, the most expensive first.
+    #[inline]
+    pub fn types(&self) -> &[(String, Timing)] {
+        &self.types
+    }
+
+    /// A table of the `limit` most expensive paths and types.
+    pub fn report(&self, limit: usize) -> Report<'_> {
+        Report {
+            profile: self,
+            limit,
+        }
+    }
+}
+
+/// See [`Profile::report`].
+pub struct Report<'a> {
+    profile: &'a Profile,
+    limit: usize,
+}
+
+impl fmt::Display for Report<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn table<K: fmt::Display>(
+            f: &mut fmt::Formatter<'_>,
+            name: &str,
+            rows: &[(K, Timing)],
+            limit: usize,
+        ) -> fmt::Result {
+            writeln!(f, "{:>10} {:>10} {:>8}  {}", "total", "self", "calls", name)?;
+            for (key, timing) in rows.iter().take(limit) {
+                writeln!(
+                    f,
+                    "{:>10} {:>10} {:>8}  {}",
+                    format!("{:.1?}", timing.total),
+                    format!("{:.1?}", timing.self_time),
+                    timing.calls,
+                    key
+                )?;
+            }
+            Ok(())
+        }
+        table(f, "path", &self.profile.paths, self.limit)?;
+        f.write_str("\n")?;
+        table(f, "type", &self.profile.types, self.limit)
+    }
+}
+
+/// Builds a [`Profile`] by pairing the exit events with their enter events.
+#[derive(Debug, Default)]
+pub(crate) struct Profiler {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    calls: CallStack<Frame>,
+    paths: HashMap<Path, Timing>,
+    types: HashMap<String, Timing>,
+}
+
+#[derive(Debug)]
+struct Frame {
+    path: Path,
+    type_name: Option<String>,
+    /// Whether the call is the outermost one at its path, or producing its type.
+    outer_path: bool,
+    outer_type: bool,
+}
+
+impl Profiler {
+    pub(crate) fn new() -> Self {
+        Default::default()
+    }
+
+    pub(crate) fn finish(&self) -> Profile {
+        let state = std::mem::take(&mut *self.state.lock().unwrap());
+        Profile {
+            paths: ranked(state.paths),
+            types: ranked(state.types),
+        }
+    }
+}
+
+fn ranked<K>(timings: HashMap<K, Timing>) -> Vec<(K, Timing)>
+where
+    K: Eq + Hash,
+{
+    let mut timings: Vec<_> = timings.into_iter().collect();
+    timings.sort_by_key(|(_, timing)| Reverse(timing.total));
+    timings
+}
+
+fn add(timing: &mut Timing, total: Duration, self_time: Duration) {
+    timing.calls += 1;
+    timing.total += total;
+    timing.self_time += self_time;
+}
+
+impl Sink for Profiler {
+    fn event(&self, event: &Event<'_>) {
+        let mut state = self.state.lock().unwrap();
+        // Only calls are frames, single events like `is_human_readable` are left out.
+        if event.is_enter() {
+            let parent = state.calls.last();
+            let frame = Frame {
+                path: event.path.clone(),
+                type_name: event.type_name.map(str::to_owned),
+                outer_path: !matches!(parent, Some(p) if p.path == *event.path),
+                outer_type: !matches!(parent, Some(p) if p.type_name.as_deref() == event.type_name),
+            };
+            state.calls.enter(event, frame);
+            return;
+        }
+        let Some(elapsed) = event.elapsed else {
+            return;
+        };
+        let Some((frame, self_time)) = state.calls.exit(event) else {
+            return;
+        };
+        // Only the outermost calls at a path or for a type count towards its total, the others
+        // are already included in it.
+        let outer = |outer: bool| if outer { elapsed } else { Duration::ZERO };
+        let path_total = outer(frame.outer_path);
+        let type_total = outer(frame.outer_type);
+        add(
+            state.paths.entry(frame.path).or_default(),
+            path_total,
+            self_time,
+        );
+        if let Some(type_name) = frame.type_name {
+            add(
+                state.types.entry(type_name).or_default(),
+                type_total,
+                self_time,
+            );
+        }
+    }
+}
+
+/// Pairs the exit events with their enter events, keeping track of the time spent in the calls
+/// nested in each call to tell its self time.
+#[derive(Debug)]
+pub(crate) struct CallStack<T> {
+    frames: Vec<CallFrame<T>>,
+}
+
+#[derive(Debug)]
+struct CallFrame<T> {
+    kind: Kind,
+    method: String,
+    depth: u32,
+    nested: Duration,
+    data: T,
+}
+
+impl<T> Default for CallStack<T> {
+    fn default() -> Self {
+        Self { frames: Vec::new() }
+    }
+}
+
+impl<T> CallStack<T> {
+    /// The data of the innermost event entered and not exited yet.
+    #[inline]
+    pub(crate) fn last(&self) -> Option<&T> {
+        self.frames.last().map(|f| &f.data)
+    }
+
+    pub(crate) fn enter(&mut self, event: &Event<'_>, data: T) {
+        self.frames.push(CallFrame {
+            kind: event.kind,
+            method: event.method.to_owned(),
+            depth: event.depth,
+            nested: Duration::ZERO,
+            data,
+        });
+    }
+
+    /// The data of the call exited by `event` with its self time.
+    pub(crate) fn exit(&mut self, event: &Event<'_>) -> Option<(T, Duration)> {
+        let elapsed = event.elapsed?;
+        // A call left without an exit is popped along the way.
+        let mut nested = Duration::ZERO;
+        let frame = loop {
+            let frame = self.frames.pop()?;
+            if frame.kind == event.kind
+                && frame.depth == event.depth
+                && frame.method == event.method
+            {
+                break frame;
+            }
+            nested += frame.nested;
+        };
+        if let Some(parent) = self.frames.last_mut() {
+            parent.nested += elapsed;
+        }
+        Some((frame.data, elapsed.saturating_sub(frame.nested + nested)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Outcome;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    /// Sends the enter event of a call, the events of the calls nested in it, then its exit
+    /// event taking `ms` milliseconds.
+    fn call<F>(
+        sink: &dyn Sink,
+        method: &str,
+        type_name: &str,
+        path: &Path,
+        depth: u32,
+        ms: u32,
+        f: F,
+    ) where
+        F: FnOnce(),
+    {
+        let kind = match method {
+            "deserialize" => Kind::Deserialize,
+            _ => Kind::Deserializer,
+        };
+        let ok = Outcome::Ok;
+        let mut event = Event {
+            kind,
+            method,
+            type_name: Some(type_name),
+            scope: None,
+            depth,
+            path,
+            args: &[],
+            value: None,
+            outcome: None,
+            elapsed: None,
+            enter: true,
+        };
+        sink.event(&event);
+        f();
+        event.enter = false;
+        event.outcome = Some(&ok);
+        event.elapsed = Some(MS * ms);
+        sink.event(&event);
+    }
+
+    /// Sends an event that is not a call, without an exit.
+    fn single(sink: &dyn Sink, method: &str, path: &Path, depth: u32) {
+        sink.event(&Event {
+            kind: Kind::Deserializer,
+            method,
+            type_name: None,
+            scope: None,
+            depth,
+            path,
+            args: &[],
+            value: None,
+            outcome: None,
+            elapsed: None,
+            enter: false,
+        });
+    }
+
+    #[test]
+    fn timings() {
+        let profiler = Profiler::new();
+        let p = &profiler;
+        let root = Path::root();
+        let path = |pointer: &[&'static str]| {
+            pointer.iter().fold(Path::root(), |path, key| {
+                path.join(crate::Segment::Key((*key).into()))
+            })
+        };
+        call(p, "deserialize_struct", "Outer", &root, 0, 100, || {
+            call(
+                p,
+                "deserialize_struct",
+                "Inner",
+                &path(&["a"]),
+                4,
+                60,
+                || {
+                    call(
+                        p,
+                        "deserialize_u32",
+                        "u32",
+                        &path(&["a", "x"]),
+                        8,
+                        50,
+                        || {},
+                    );
+                    call(p, "deserialize_u32", "u32", &path(&["a", "y"]), 8, 5, || {});
+                },
+            );
+            call(p, "deserialize_seq", "Vec", &path(&["b"]), 4, 20, || {
+                call(p, "deserialize_u32", "u32", &path(&["b", "z"]), 8, 1, || {});
+            });
+        });
+        let profile = profiler.finish();
+
+        let timing = |calls, total, self_time| Timing {
+            calls,
+            total: MS * total,
+            self_time: MS * self_time,
+        };
+        let paths: Vec<_> = profile
+            .paths()
+            .iter()
+            .map(|(path, timing)| (path.to_string(), *timing))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                ("/".to_owned(), timing(1, 100, 20)),
+                ("/a".to_owned(), timing(1, 60, 5)),
+                ("/a/x".to_owned(), timing(1, 50, 50)),
+                ("/b".to_owned(), timing(1, 20, 19)),
+                ("/a/y".to_owned(), timing(1, 5, 5)),
+                ("/b/z".to_owned(), timing(1, 1, 1)),
+            ]
+        );
+        assert_eq!(
+            profile.types(),
+            [
+                ("Outer".to_owned(), timing(1, 100, 20)),
+                ("Inner".to_owned(), timing(1, 60, 5)),
+                ("u32".to_owned(), timing(3, 56, 56)),
+                ("Vec".to_owned(), timing(1, 20, 19)),
+            ]
+        );
+    }
+
+    #[test]
+    fn single_events_are_not_frames() {
+        let profiler = Profiler::new();
+        let root = Path::root();
+        call(&profiler, "deserialize", "u32", &root, 0, 10, || {
+            // Not the parent of the call after it, which is still nested in the outer one.
+            single(&profiler, "is_human_readable", &root, 1);
+            call(&profiler, "deserialize_u32", "u32", &root, 1, 3, || {});
+        });
+        let profile = profiler.finish();
+        let timing = Timing {
+            calls: 2,
+            total: MS * 10,
+            self_time: MS * 10,
+        };
+        assert_eq!(profile.types(), [("u32".to_owned(), timing)]);
+        assert_eq!(profile.paths(), [(root, timing)]);
+    }
+
+    #[test]
+    fn no_frames_without_calls() {
+        // Like the serializer events, which never exit.
+        let profiler = Profiler::new();
+        let root = Path::root();
+        for _ in 0..100 {
+            single(&profiler, "serialize_u8", &root, 0);
+        }
+        assert!(profiler.state.lock().unwrap().calls.last().is_none());
+        assert!(profiler.finish().paths().is_empty());
+    }
+}
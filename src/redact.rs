@@ -0,0 +1,280 @@
+use crate::{Path, Segment, Value};
+use std::borrow::Cow;
+
+/// How the values matched by a redaction rule are logged, see
+/// [`Config::redact`](crate::Config::redact).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Redaction {
+    /// Logged as `<redacted>`.
+    #[default]
+    Mask,
+    /// Logged as a hash that stays the same across runs, so equal values can still be told apart
+    /// from different ones.
+    Hash,
+}
+
+/// The redaction rules of a [`Config`](crate::Config).
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Rules {
+    patterns: Vec<Pattern>,
+    redaction: Redaction,
+}
+
+impl Rules {
+    pub(crate) fn push(&mut self, rule: &str) {
+        self.patterns.push(Pattern::parse(rule));
+    }
+
+    pub(crate) fn set_redaction(&mut self, redaction: Redaction) {
+        self.redaction = redaction;
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    pub(crate) fn matches(&self, path: &Path) -> bool {
+        if self.is_empty() {
+            return false;
+        }
+        let segments: Vec<_> = path.segments().into_iter().map(segment_text).collect();
+        self.patterns.iter().any(|p| p.matches(&segments))
+    }
+
+    pub(crate) fn redact(&self, value: &Value<'_>) -> Value<'static> {
+        match self.redaction {
+            Redaction::Mask => Value::Redacted(None),
+            Redaction::Hash => Value::Redacted(Some(fnv1a(value.to_string().as_bytes()))),
+        }
+    }
+}
+
+fn segment_text(segment: &Segment) -> Cow<'_, str> {
+    match segment {
+        Segment::Key(key) | Segment::Variant(key) => Cow::Borrowed(key),
+        Segment::Index(index) => Cow::Owned(index.to_string()),
+    }
+}
+
+/// A rule matched against the segments of a path.
+///
+/// Rules starting with a `/` are JSON pointers matched from the root, others are dot-separated
+/// and matched against the end of the path, so `password` matches any `password` field. `*`
+/// matches within a segment and `**` matches any number of segments, at least one when trailing.
+#[derive(Clone, Debug)]
+struct Pattern(Vec<Component>);
+
+#[derive(Clone, Debug)]
+enum Component {
+    Any,
+    Glob(String),
+}
+
+impl Pattern {
+    fn parse(rule: &str) -> Self {
+        let mut components = Vec::new();
+        let segments: Vec<Cow<'_, str>> = match rule.strip_prefix('/') {
+            Some(pointer) => pointer
+                .split('/')
+                .map(|s| Cow::Owned(s.replace("~1", "/").replace("~0", "~")))
+                .collect(),
+            None => {
+                components.push(Component::Any);
+                rule.split('.').map(Cow::Borrowed).collect()
+            }
+        };
+        components.extend(segments.into_iter().map(|s| match &*s {
+            "**" => Component::Any,
+            _ => Component::Glob(s.into_owned()),
+        }));
+        Self(components)
+    }
+
+    fn matches<S>(&self, segments: &[S]) -> bool
+    where
+        S: AsRef<str>,
+    {
+        fn go<S: AsRef<str>>(components: &[Component], segments: &[S]) -> bool {
+            match components.split_first() {
+                None => segments.is_empty(),
+                // A trailing `**` only matches what is nested, not the parent itself.
+                Some((Component::Any, [])) => !segments.is_empty(),
+                Some((Component::Any, rest)) => {
+                    (0..=segments.len()).any(|i| go(rest, &segments[i..]))
+                }
+                Some((Component::Glob(glob), rest)) => match segments.split_first() {
+                    Some((segment, segments)) => {
+                        glob_matches(glob, segment.as_ref()) && go(rest, segments)
+                    }
+                    None => false,
+                },
+            }
+        }
+        go(&self.0, segments)
+    }
+}
+
+fn glob_matches(glob: &str, text: &str) -> bool {
+    match glob.split_once('*') {
+        None => glob == text,
+        Some((prefix, rest)) => match text.strip_prefix(prefix) {
+            Some(text) => (0..=text.len())
+                .filter(|&i| text.is_char_boundary(i))
+                .any(|i| glob_matches(rest, &text[i..])),
+            None => false,
+        },
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::Context;
+    use std::sync::Arc;
+
+    fn parse(rules: &[&str]) -> Rules {
+        let mut out = Rules::default();
+        for rule in rules {
+            out.push(rule);
+        }
+        out
+    }
+
+    fn path(pointer: &str) -> Path {
+        pointer
+            .split('/')
+            .skip(1)
+            .fold(Path::root(), |path, segment| match segment.parse() {
+                Ok(index) => path.join(Segment::Index(index)),
+                Err(_) => path.join(Segment::Key(segment.to_owned().into())),
+            })
+    }
+
+    #[test]
+    fn suffix_rules() {
+        let rules = parse(&["password", "*.token"]);
+        assert!(rules.matches(&path("/password")));
+        assert!(rules.matches(&path("/users/0/password")));
+        assert!(!rules.matches(&path("/password_hint")));
+        assert!(!rules.matches(&path("/token")));
+        assert!(rules.matches(&path("/auth/token")));
+        assert!(rules.matches(&path("/a/b/token")));
+        assert!(!rules.matches(&Path::root()));
+    }
+
+    #[test]
+    fn pointer_rules_are_anchored() {
+        let rules = parse(&["/auth/key"]);
+        assert!(rules.matches(&path("/auth/key")));
+        assert!(!rules.matches(&path("/nested/auth/key")));
+        assert!(!rules.matches(&path("/auth/key/more")));
+        assert!(!rules.matches(&path("/auth")));
+
+        let rules = Rules::default();
+        assert!(!rules.matches(&path("/auth/key")));
+    }
+
+    #[test]
+    fn pointer_escapes() {
+        let rules = parse(&["/a~1b/c~0d"]);
+        let path = Path::root()
+            .join(Segment::Key("a/b".into()))
+            .join(Segment::Key("c~d".into()));
+        assert!(rules.matches(&path));
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_matches("*", ""));
+        assert!(glob_matches("*", "anything"));
+        assert!(glob_matches("api_*", "api_key"));
+        assert!(!glob_matches("api_*", "key_api"));
+        assert!(glob_matches("*_key", "api_key"));
+        assert!(glob_matches("a*b*c", "aXXbYYc"));
+        assert!(!glob_matches("a*b*c", "aXXcYYb"));
+        assert!(glob_matches("é*", "été"));
+
+        let rules = parse(&["/users/*/secret_*"]);
+        assert!(rules.matches(&path("/users/3/secret_key")));
+        assert!(!rules.matches(&path("/users/secret_key")));
+        assert!(!rules.matches(&path("/users/3/4/secret_key")));
+    }
+
+    #[test]
+    fn double_star() {
+        let rules = parse(&["/auth/**"]);
+        assert!(!rules.matches(&path("/auth")));
+        assert!(rules.matches(&path("/auth/key")));
+        assert!(rules.matches(&path("/auth/keys/0")));
+        assert!(!rules.matches(&path("/other/key")));
+
+        let rules = parse(&["/**/secret"]);
+        assert!(rules.matches(&path("/secret")));
+        assert!(rules.matches(&path("/a/b/secret")));
+        assert!(!rules.matches(&path("/a/secret/b")));
+
+        let rules = parse(&["config.**.password"]);
+        assert!(rules.matches(&path("/config/password")));
+        assert!(rules.matches(&path("/app/config/db/0/password")));
+        assert!(!rules.matches(&path("/config/password/hint")));
+    }
+
+    #[test]
+    fn mask() {
+        let rules = parse(&["password"]);
+        let value = Value::Str("hunter2".into());
+        assert_eq!(rules.redact(&value), Value::Redacted(None));
+        assert_eq!(rules.redact(&value).to_string(), "<redacted>");
+    }
+
+    #[test]
+    fn hash() {
+        let mut rules = parse(&["password"]);
+        rules.set_redaction(Redaction::Hash);
+        let a = rules.redact(&Value::Str("hunter2".into()));
+        let b = rules.redact(&Value::Str("hunter2".into()));
+        let c = rules.redact(&Value::Str("hunter3".into()));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(matches!(a, Value::Redacted(Some(_))));
+        let shown = a.to_string();
+        assert!(shown.starts_with("<redacted:") && shown.len() == "<redacted:>".len() + 16);
+        assert!(!shown.contains("hunter2"));
+        // Stays the same across runs.
+        assert_eq!(
+            Value::Redacted(Some(fnv1a(b"hunter2"))).to_string(),
+            format!("<redacted:{:016x}>", fnv1a(b"hunter2"))
+        );
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+
+    #[test]
+    fn redact_error() {
+        let ctx =
+            Context::new(Arc::new(crate::sink::Collector::new())).with_rules(parse(&["password"]));
+        let message = "invalid value: string \"hunter2\"".to_owned();
+        // Raised on a redacted value.
+        assert_eq!(ctx.redact_error(message.clone(), true), "<redacted>");
+        // The same error propagating through the calls above it.
+        assert_eq!(ctx.redact_error(message.clone(), false), "<redacted>");
+        assert_eq!(ctx.redact_error(message, false), "<redacted>");
+        // Other errors are kept.
+        let other = "missing field `name`".to_owned();
+        assert_eq!(ctx.redact_error(other.clone(), false), other);
+    }
+
+    #[test]
+    fn redact_error_without_rules() {
+        let ctx = Context::new(Arc::new(crate::sink::Collector::new()));
+        let message = "invalid value: string \"hunter2\"".to_owned();
+        assert_eq!(ctx.redact_error(message.clone(), true), message);
+    }
+}
@@ -0,0 +1,401 @@
+use crate::{Kind, Outcome, Record, Trace, Value};
+use serde::de;
+use std::fmt;
+
+/// Deserializes by replaying the visitor calls of a recorded [`Trace`], without the format it
+/// was recorded from.
+///
+/// The trace has to hold every event, as recorded by [`record`](crate::record), and is followed
+/// as long as the types being deserialized make the same calls. A failure recorded in the trace
+/// is replayed as an error.
+///
+/// ```
+/// # #[cfg(not(feature = "disabled"))]
+/// # {
+/// let (value, trace) =
+///     serde_log::record::<_, Vec<u32>>(&mut serde_json::Deserializer::from_str("[1, 2]"));
+/// let saved = serde_json::to_string(&trace).unwrap();
+///
+/// let trace: serde_log::Trace = serde_json::from_str(&saved).unwrap();
+/// let mut replay = serde_log::ReplayDeserializer::new(&trace);
+/// let replayed: Vec<u64> = serde::Deserialize::deserialize(&mut replay).unwrap();
+/// assert_eq!(replayed, [1, 2]);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ReplayDeserializer<'de> {
+    pub(crate) records: &'de [Record],
+    pub(crate) pos: usize,
+    human_readable: bool,
+}
+
+impl<'de> ReplayDeserializer<'de> {
+    pub fn new(trace: &'de Trace) -> Self {
+        let records = trace.records();
+        let human_readable = records
+            .iter()
+            .find(|r| r.method == "is_human_readable")
+            .and_then(|r| match r.value {
+                Some(Value::Bool(v)) => Some(v),
+                _ => None,
+            })
+            .unwrap_or(true);
+        Self {
+            records,
+            pos: 0,
+            human_readable,
+        }
+    }
+
+    /// Moves past the next visitor call, replaying the failure of the call it is nested in if
+    /// there is none.
+    pub(crate) fn next_visit(&mut self) -> Result<usize, ReplayError> {
+        while let Some(record) = self.records.get(self.pos) {
+            match &record.outcome {
+                None if record.kind == Kind::Visitor => {
+                    self.pos += 1;
+                    return Ok(self.pos - 1);
+                }
+                Some(Outcome::Err(e)) => return Err(ReplayError(e.to_string())),
+                _ => self.pos += 1,
+            }
+        }
+        Err(ReplayError::end())
+    }
+
+    /// Moves to the next call of `kind` at `depth`, or to the end of the call it is nested in
+    /// when there are no more.
+    fn seek(&mut self, kind: Kind, depth: u32) -> Result<Option<usize>, ReplayError> {
+        while let Some(record) = self.records.get(self.pos) {
+            match &record.outcome {
+                None if record.kind == kind && record.depth == depth => return Ok(Some(self.pos)),
+                Some(Outcome::Err(e)) if record.depth < depth => {
+                    return Err(ReplayError(e.to_string()))
+                }
+                Some(_) if record.depth < depth => return Ok(None),
+                _ => self.pos += 1,
+            }
+        }
+        Err(ReplayError::end())
+    }
+
+    /// Whether the call entered at `index` returned right away, without nested calls, replaying
+    /// its failure if it failed.
+    fn is_empty(&self, index: usize) -> Result<bool, ReplayError> {
+        let Some(next) = self.records.get(index + 1) else {
+            return Err(ReplayError::end());
+        };
+        match &next.outcome {
+            Some(Outcome::Err(e)) if next.depth == self.records[index].depth => {
+                Err(ReplayError(e.to_string()))
+            }
+            Some(_) => Ok(next.depth == self.records[index].depth),
+            None => Ok(false),
+        }
+    }
+
+    /// Moves past the exit of the call entered at `index`.
+    pub(crate) fn skip_call(&mut self, index: usize) {
+        let enter = &self.records[index];
+        self.pos = self.records[index + 1..]
+            .iter()
+            .position(|r| {
+                r.is_exit()
+                    && r.depth == enter.depth
+                    && r.kind == enter.kind
+                    && r.method == enter.method
+            })
+            .map_or(self.records.len(), |i| index + i + 2);
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut ReplayDeserializer<'de> {
+    type Error = ReplayError;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let index = self.next_visit()?;
+        let records = self.records;
+        let record = &records[index];
+        let depth = record.depth + 1;
+        let value = match (record.method.as_ref(), &record.value) {
+            (_, Some(Value::Bool(v))) => visitor.visit_bool(*v),
+            (_, Some(Value::I8(v))) => visitor.visit_i8(*v),
+            (_, Some(Value::I16(v))) => visitor.visit_i16(*v),
+            (_, Some(Value::I32(v))) => visitor.visit_i32(*v),
+            (_, Some(Value::I64(v))) => visitor.visit_i64(*v),
+            (_, Some(Value::I128(v))) => visitor.visit_i128(*v),
+            (_, Some(Value::U8(v))) => visitor.visit_u8(*v),
+            (_, Some(Value::U16(v))) => visitor.visit_u16(*v),
+            (_, Some(Value::U32(v))) => visitor.visit_u32(*v),
+            (_, Some(Value::U64(v))) => visitor.visit_u64(*v),
+            (_, Some(Value::U128(v))) => visitor.visit_u128(*v),
+            (_, Some(Value::F32(v))) => visitor.visit_f32(*v),
+            (_, Some(Value::F64(v))) => visitor.visit_f64(*v),
+            (_, Some(Value::Char(v))) => visitor.visit_char(*v),
+            ("visit_borrowed_str", Some(Value::Str(v))) => visitor.visit_borrowed_str(v),
+            ("visit_string", Some(Value::Str(v))) => visitor.visit_string(v.to_string()),
+            (_, Some(Value::Str(v))) => visitor.visit_str(v),
+            ("visit_borrowed_bytes", Some(Value::Bytes(v))) => visitor.visit_borrowed_bytes(v),
+            ("visit_byte_buf", Some(Value::Bytes(v))) => visitor.visit_byte_buf(v.to_vec()),
+            (_, Some(Value::Bytes(v))) => visitor.visit_bytes(v),
+            (_, Some(Value::Redacted(_))) => Err(ReplayError(format!(
+                "the value at {} was redacted when recorded",
+                record.path
+            ))),
+            ("visit_none", _) => visitor.visit_none(),
+            ("visit_unit", _) => visitor.visit_unit(),
+            ("visit_some", _) => visitor.visit_some(&mut *self),
+            ("visit_newtype_struct", _) => visitor.visit_newtype_struct(&mut *self),
+            ("visit_seq", _) => visitor.visit_seq(Access::new(self, depth)),
+            ("visit_map", _) => visitor.visit_map(Access::new(self, depth)),
+            ("visit_enum", _) => visitor.visit_enum(Access::new(self, depth)),
+            (method, _) => Err(ReplayError(format!("cannot replay `{}`", method))),
+        }?;
+        self.skip_call(index);
+        Ok(value)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.human_readable
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf
+        option unit unit_struct newtype_struct seq tuple tuple_struct map struct enum
+        identifier ignored_any
+    }
+}
+
+/// Replays the `SeqAccess`, `MapAccess`, `EnumAccess` and `VariantAccess` calls at `depth`.
+pub(crate) struct Access<'a, 'de> {
+    de: &'a mut ReplayDeserializer<'de>,
+    depth: u32,
+    /// The `next_entry` call whose value is up next.
+    entry: Option<usize>,
+}
+
+impl<'a, 'de> Access<'a, 'de> {
+    pub(crate) fn new(de: &'a mut ReplayDeserializer<'de>, depth: u32) -> Self {
+        Self {
+            de,
+            depth,
+            entry: None,
+        }
+    }
+
+    /// Moves to the next call of `kind`, failing when there are no more.
+    pub(crate) fn expect(&mut self, kind: Kind, expected: &str) -> Result<usize, ReplayError> {
+        self.de
+            .seek(kind, self.depth)?
+            .ok_or_else(|| ReplayError(format!("expected {}", expected)))
+    }
+
+    /// Moves past the call entered at `index`.
+    pub(crate) fn skip_call(&mut self, index: usize) {
+        self.de.skip_call(index);
+    }
+
+    /// Deserializes what was read by the call entered at `index`, then moves past it.
+    pub(crate) fn nested<T, F>(
+        &mut self,
+        index: usize,
+        expected: &str,
+        f: F,
+    ) -> Result<T, ReplayError>
+    where
+        F: FnOnce(&mut ReplayDeserializer<'de>) -> Result<T, ReplayError>,
+    {
+        if self.de.is_empty(index)? {
+            return Err(ReplayError(format!(
+                "expected {}, `{}` returned nothing",
+                expected, self.de.records[index].method
+            )));
+        }
+        self.de.pos = index + 1;
+        let value = f(self.de)?;
+        self.de.skip_call(index);
+        Ok(value)
+    }
+}
+
+impl<'de> Access<'_, 'de> {
+    pub(crate) fn next_element<T, F>(&mut self, f: F) -> Result<Option<T>, ReplayError>
+    where
+        F: FnOnce(&mut ReplayDeserializer<'de>) -> Result<T, ReplayError>,
+    {
+        let Some(index) = self.de.seek(Kind::SeqAccess, self.depth)? else {
+            return Ok(None);
+        };
+        if self.de.is_empty(index)? {
+            self.de.skip_call(index);
+            return Ok(None);
+        }
+        self.nested(index, "an element", f).map(Some)
+    }
+
+    pub(crate) fn next_key<T, F>(&mut self, f: F) -> Result<Option<T>, ReplayError>
+    where
+        F: FnOnce(&mut ReplayDeserializer<'de>) -> Result<T, ReplayError>,
+    {
+        let Some(index) = self.de.seek(Kind::MapAccess, self.depth)? else {
+            return Ok(None);
+        };
+        let records = self.de.records;
+        let method = &records[index].method;
+        if method.starts_with("next_value") {
+            return Err(ReplayError(format!("expected a key, found `{}`", method)));
+        }
+        if self.de.is_empty(index)? {
+            self.de.skip_call(index);
+            return Ok(None);
+        }
+        if method.starts_with("next_entry") {
+            // The key and the value are read by the same call, each in their own seed.
+            self.de.pos = index + 1;
+            let key = f(self.de)?;
+            self.de.skip_call(index + 1);
+            self.entry = Some(index);
+            return Ok(Some(key));
+        }
+        self.nested(index, "a key", f).map(Some)
+    }
+
+    pub(crate) fn next_value<T, F>(&mut self, f: F) -> Result<T, ReplayError>
+    where
+        F: FnOnce(&mut ReplayDeserializer<'de>) -> Result<T, ReplayError>,
+    {
+        if let Some(index) = self.entry.take() {
+            let value = f(self.de)?;
+            self.de.skip_call(index);
+            return Ok(value);
+        }
+        let index = self.expect(Kind::MapAccess, "a value")?;
+        self.nested(index, "a value", f)
+    }
+
+    pub(crate) fn next_variant<T, F>(&mut self, f: F) -> Result<T, ReplayError>
+    where
+        F: FnOnce(&mut ReplayDeserializer<'de>) -> Result<T, ReplayError>,
+    {
+        let index = self.expect(Kind::EnumAccess, "a variant")?;
+        let variant = self.nested(index, "a variant", f)?;
+        // The variant is accessed one level below the enum.
+        self.depth += 1;
+        Ok(variant)
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for Access<'_, 'de> {
+    type Error = ReplayError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        self.next_element(|de| seed.deserialize(de))
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Access<'_, 'de> {
+    type Error = ReplayError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        self.next_key(|de| seed.deserialize(de))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        self.next_value(|de| seed.deserialize(de))
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for Access<'_, 'de> {
+    type Error = ReplayError;
+    type Variant = Self;
+
+    fn variant_seed<V>(mut self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let variant = self.next_variant(|de| seed.deserialize(de))?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for Access<'_, 'de> {
+    type Error = ReplayError;
+
+    fn unit_variant(mut self) -> Result<(), Self::Error> {
+        let index = self.expect(Kind::VariantAccess, "a unit variant")?;
+        self.skip_call(index);
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(mut self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        let index = self.expect(Kind::VariantAccess, "a newtype variant")?;
+        self.nested(index, "a newtype variant", |de| seed.deserialize(de))
+    }
+
+    fn tuple_variant<V>(mut self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let index = self.expect(Kind::VariantAccess, "a tuple variant")?;
+        self.nested(index, "a tuple variant", |de| {
+            de::Deserializer::deserialize_any(de, visitor)
+        })
+    }
+
+    fn struct_variant<V>(
+        mut self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: de::Visitor<'de>,
+    {
+        let index = self.expect(Kind::VariantAccess, "a struct variant")?;
+        self.nested(index, "a struct variant", |de| {
+            de::Deserializer::deserialize_any(de, visitor)
+        })
+    }
+}
+
+/// An error replaying a [`Trace`], either recorded in it or because the types deserialized
+/// do not follow it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReplayError(String);
+
+impl ReplayError {
+    fn end() -> Self {
+        Self("unexpected end of trace".to_owned())
+    }
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl de::Error for ReplayError {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Self(msg.to_string())
+    }
+}
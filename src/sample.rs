@@ -0,0 +1,128 @@
+use crate::{context::Context, Event, Kind, Path, Record, Value, Wrapper};
+use std::{collections::VecDeque, sync::Arc, time::Instant};
+
+/// How many elements of each sequence and map are logged, see
+/// [`Config::sample`](crate::Config::sample).
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Sampling {
+    pub(crate) head: usize,
+    pub(crate) tail: usize,
+}
+
+/// A sampled sequence or map, holding on to the events of its last elements until it ends and
+/// counting the others.
+pub(crate) struct Sample {
+    ctx: Arc<Context>,
+    kind: Kind,
+    scope: Option<Arc<str>>,
+    depth: u32,
+    path: Path,
+    start: Instant,
+    tail: VecDeque<Vec<Record>>,
+    limit: usize,
+    elided: usize,
+    buffering: bool,
+}
+
+impl Sample {
+    fn new<A>(wrapper: &Wrapper<A>, kind: Kind, limit: usize) -> Self {
+        Self {
+            ctx: wrapper.ctx.clone(),
+            kind,
+            scope: wrapper.scope.clone(),
+            depth: wrapper.depth,
+            path: wrapper.path.clone(),
+            start: Instant::now(),
+            tail: VecDeque::with_capacity(limit),
+            limit,
+            elided: 0,
+            buffering: false,
+        }
+    }
+
+    fn end_element(&mut self) {
+        if !self.buffering {
+            return;
+        }
+        self.buffering = false;
+        let records = self.ctx.end_buffer();
+        if self.tail.len() == self.limit {
+            self.tail.pop_front();
+            self.elided += 1;
+        }
+        if self.limit > 0 {
+            self.tail.push_back(records);
+        }
+    }
+}
+
+/// Logs the buffered elements, then how many were left out and the time spent on the whole
+/// collection.
+impl Drop for Sample {
+    fn drop(&mut self) {
+        self.end_element();
+        for records in self.tail.drain(..) {
+            self.ctx.replay(records);
+        }
+        if self.elided > 0 {
+            self.ctx.emit(&Event {
+                kind: self.kind,
+                method: "elided",
+                type_name: None,
+                scope: self.scope.as_deref(),
+                depth: self.depth,
+                path: &self.path,
+                args: &[
+                    ("count", self.elided.into()),
+                    ("elapsed", Value::Duration(self.start.elapsed())),
+                ],
+                value: None,
+                outcome: None,
+                elapsed: None,
+                enter: false,
+            });
+        }
+    }
+}
+
+impl<A> Wrapper<A> {
+    /// Starts buffering the events of the next element once past the sampled head.
+    pub(crate) fn begin_element(&mut self, kind: Kind) {
+        let Some(sampling) = self.ctx.sampling() else {
+            return;
+        };
+        if self.sample.is_none() {
+            self.sample = Some(Box::new(Sample::new(self, kind, sampling.tail)));
+        }
+        let past_head = self.index >= sampling.head;
+        if let Some(sample) = self.sample.as_mut().filter(|_| past_head) {
+            self.ctx.begin_buffer();
+            sample.buffering = true;
+        }
+    }
+
+    /// Keeps the events of the element that was just read if it could be one of the last.
+    pub(crate) fn end_element(&mut self) {
+        if let Some(sample) = &mut self.sample {
+            sample.end_element();
+        }
+    }
+
+    /// Ends the collection, logging its last elements and then the events of the call that
+    /// ended it, either by returning `None` or by failing.
+    pub(crate) fn end_collection(&mut self) {
+        let Some(mut sample) = self.sample.take() else {
+            return;
+        };
+        let last = if sample.buffering {
+            sample.buffering = false;
+            Some(self.ctx.end_buffer())
+        } else {
+            None
+        };
+        drop(sample);
+        if let Some(records) = last {
+            self.ctx.replay(records);
+        }
+    }
+}
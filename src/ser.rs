@@ -0,0 +1,574 @@
+use crate::{Kind, Segment, Wrapper};
+use serde::ser;
+
+#[cfg(not(feature = "disabled"))]
+impl<T> ser::Serialize for Wrapper<T>
+where
+    T: ser::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        self.emit(Kind::Serialize, "serialize", &[], None);
+        let serializer = self.sub_wrap(serializer);
+        self.inner.serialize(serializer)
+    }
+}
+
+#[cfg(not(feature = "disabled"))]
+impl<S> ser::Serializer for Wrapper<S>
+where
+    S: ser::Serializer,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+    type SerializeSeq = Wrapper<S::SerializeSeq>;
+    type SerializeTuple = Wrapper<S::SerializeTuple>;
+    type SerializeTupleStruct = Wrapper<S::SerializeTupleStruct>;
+    type SerializeTupleVariant = Wrapper<S::SerializeTupleVariant>;
+    type SerializeMap = Wrapper<S::SerializeMap>;
+    type SerializeStruct = Wrapper<S::SerializeStruct>;
+    type SerializeStructVariant = Wrapper<S::SerializeStructVariant>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_bool", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_i8", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_i16", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_i32", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_i64", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_i128", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_u8", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_u16", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_u32", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_u64", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_u128", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_f32", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_f64", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_char", &[], Some(v.into()));
+        self.capture(|| v.to_string());
+        self.inner.serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_str", &[], Some(v.into()));
+        self.capture(|| v.to_owned());
+        self.inner.serialize_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_bytes", &[], Some(v.into()));
+        self.capture(|| String::from_utf8_lossy(v).into_owned());
+        self.inner.serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_none", &[], None);
+        self.inner.serialize_none()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.emit(Kind::Serializer, "serialize_some", &[], None);
+        let value = self.sub_wrap(value);
+        self.inner.serialize_some(&value)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::Serializer, "serialize_unit", &[], None);
+        self.inner.serialize_unit()
+    }
+
+    fn serialize_unit_struct(mut self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        self.enter_scope(name);
+        self.emit(
+            Kind::Serializer,
+            "serialize_unit_struct",
+            &[("name", name.into())],
+            None,
+        );
+        self.inner.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        self.enter_scope(name);
+        self.emit(
+            Kind::Serializer,
+            "serialize_unit_variant",
+            &[
+                ("name", name.into()),
+                ("variant_index", variant_index.into()),
+                ("variant", variant.into()),
+            ],
+            None,
+        );
+        self.capture(|| variant.to_owned());
+        self.inner
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        mut self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.enter_scope(name);
+        self.emit(
+            Kind::Serializer,
+            "serialize_newtype_struct",
+            &[("name", name.into())],
+            None,
+        );
+        let value = self.sub_wrap(value);
+        self.inner.serialize_newtype_struct(name, &value)
+    }
+
+    fn serialize_newtype_variant<T>(
+        mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.enter_scope(name);
+        self.emit(
+            Kind::Serializer,
+            "serialize_newtype_variant",
+            &[
+                ("name", name.into()),
+                ("variant_index", variant_index.into()),
+                ("variant", variant.into()),
+            ],
+            None,
+        );
+        let value = self.sub_wrap_at(value, Segment::Variant(variant.into()));
+        self.inner
+            .serialize_newtype_variant(name, variant_index, variant, &value)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        match len {
+            Some(len) => self.emit(
+                Kind::Serializer,
+                "serialize_seq",
+                &[("len", len.into())],
+                None,
+            ),
+            None => self.emit(Kind::Serializer, "serialize_seq", &[], None),
+        }
+        self.sub_map(|inner| inner.serialize_seq(len))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.emit(
+            Kind::Serializer,
+            "serialize_tuple",
+            &[("len", len.into())],
+            None,
+        );
+        self.sub_map(|inner| inner.serialize_tuple(len))
+    }
+
+    fn serialize_tuple_struct(
+        mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.enter_scope(name);
+        self.emit(
+            Kind::Serializer,
+            "serialize_tuple_struct",
+            &[("name", name.into()), ("len", len.into())],
+            None,
+        );
+        self.sub_map(|inner| inner.serialize_tuple_struct(name, len))
+    }
+
+    fn serialize_tuple_variant(
+        mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        self.enter_scope(name);
+        self.emit(
+            Kind::Serializer,
+            "serialize_tuple_variant",
+            &[
+                ("name", name.into()),
+                ("variant_index", variant_index.into()),
+                ("variant", variant.into()),
+                ("len", len.into()),
+            ],
+            None,
+        );
+        self.sub_map(|inner| inner.serialize_tuple_variant(name, variant_index, variant, len))
+            .map(|wrapper| wrapper.push(Segment::Variant(variant.into())))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        match len {
+            Some(len) => self.emit(
+                Kind::Serializer,
+                "serialize_map",
+                &[("len", len.into())],
+                None,
+            ),
+            None => self.emit(Kind::Serializer, "serialize_map", &[], None),
+        }
+        self.sub_map(|inner| inner.serialize_map(len))
+    }
+
+    fn serialize_struct(
+        mut self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.enter_scope(name);
+        self.emit(
+            Kind::Serializer,
+            "serialize_struct",
+            &[("name", name.into()), ("len", len.into())],
+            None,
+        );
+        self.sub_map(|inner| inner.serialize_struct(name, len))
+    }
+
+    fn serialize_struct_variant(
+        mut self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        self.enter_scope(name);
+        self.emit(
+            Kind::Serializer,
+            "serialize_struct_variant",
+            &[
+                ("name", name.into()),
+                ("variant_index", variant_index.into()),
+                ("variant", variant.into()),
+                ("len", len.into()),
+            ],
+            None,
+        );
+        self.sub_map(|inner| inner.serialize_struct_variant(name, variant_index, variant, len))
+            .map(|wrapper| wrapper.push(Segment::Variant(variant.into())))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        let human_readable = self.inner.is_human_readable();
+        self.emit(
+            Kind::Serializer,
+            "is_human_readable",
+            &[],
+            Some(human_readable.into()),
+        );
+        human_readable
+    }
+}
+
+impl<S> ser::SerializeSeq for Wrapper<S>
+where
+    S: ser::SerializeSeq,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.emit(Kind::SerializeSeq, "serialize_element", &[], None);
+        let value = self.sub_wrap_index(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::SerializeSeq, "end", &[], None);
+        self.inner.end()
+    }
+}
+
+impl<S> ser::SerializeTuple for Wrapper<S>
+where
+    S: ser::SerializeTuple,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.emit(Kind::SerializeTuple, "serialize_element", &[], None);
+        let value = self.sub_wrap_index(value);
+        self.inner.serialize_element(&value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::SerializeTuple, "end", &[], None);
+        self.inner.end()
+    }
+}
+
+impl<S> ser::SerializeTupleStruct for Wrapper<S>
+where
+    S: ser::SerializeTupleStruct,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.emit(Kind::SerializeTupleStruct, "serialize_field", &[], None);
+        let value = self.sub_wrap_index(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::SerializeTupleStruct, "end", &[], None);
+        self.inner.end()
+    }
+}
+
+impl<S> ser::SerializeTupleVariant for Wrapper<S>
+where
+    S: ser::SerializeTupleVariant,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.emit(Kind::SerializeTupleVariant, "serialize_field", &[], None);
+        let value = self.sub_wrap_index(value);
+        self.inner.serialize_field(&value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::SerializeTupleVariant, "end", &[], None);
+        self.inner.end()
+    }
+}
+
+impl<S> Wrapper<S>
+where
+    S: ser::SerializeMap,
+{
+    fn serialize_key_inner<T>(&mut self, key: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let key = self.sub_wrap(key);
+        self.arm_capture();
+        let result = self.inner.serialize_key(&key);
+        self.key = self.take_capture();
+        result
+    }
+
+    fn serialize_value_inner<T>(&mut self, value: &T) -> Result<(), S::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        let value = self.sub_wrap_value(value);
+        self.inner.serialize_value(&value)
+    }
+}
+
+impl<S> ser::SerializeMap for Wrapper<S>
+where
+    S: ser::SerializeMap,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.emit(Kind::SerializeMap, "serialize_key", &[], None);
+        self.serialize_key_inner(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.emit(Kind::SerializeMap, "serialize_value", &[], None);
+        self.serialize_value_inner(value)
+    }
+
+    fn serialize_entry<K, V>(&mut self, key: &K, value: &V) -> Result<(), Self::Error>
+    where
+        K: ?Sized + ser::Serialize,
+        V: ?Sized + ser::Serialize,
+    {
+        self.emit(Kind::SerializeMap, "serialize_entry", &[], None);
+        self.serialize_key_inner(key)?;
+        self.serialize_value_inner(value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::SerializeMap, "end", &[], None);
+        self.inner.end()
+    }
+}
+
+impl<S> ser::SerializeStruct for Wrapper<S>
+where
+    S: ser::SerializeStruct,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.emit(
+            Kind::SerializeStruct,
+            "serialize_field",
+            &[("key", key.into())],
+            None,
+        );
+        let value = self.sub_wrap_at(value, Segment::Key(key.into()));
+        self.inner.serialize_field(key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.emit(
+            Kind::SerializeStruct,
+            "skip_field",
+            &[("key", key.into())],
+            None,
+        );
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::SerializeStruct, "end", &[], None);
+        self.inner.end()
+    }
+}
+
+impl<S> ser::SerializeStructVariant for Wrapper<S>
+where
+    S: ser::SerializeStructVariant,
+{
+    type Ok = S::Ok;
+    type Error = S::Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + ser::Serialize,
+    {
+        self.emit(
+            Kind::SerializeStructVariant,
+            "serialize_field",
+            &[("key", key.into())],
+            None,
+        );
+        let value = self.sub_wrap_at(value, Segment::Key(key.into()));
+        self.inner.serialize_field(key, &value)
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<(), Self::Error> {
+        self.emit(
+            Kind::SerializeStructVariant,
+            "skip_field",
+            &[("key", key.into())],
+            None,
+        );
+        self.inner.skip_field(key)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.emit(Kind::SerializeStructVariant, "end", &[], None);
+        self.inner.end()
+    }
+}
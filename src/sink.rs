@@ -0,0 +1,223 @@
+use crate::{Event, Record};
+use std::{borrow::Cow, fmt, sync::Arc, sync::Mutex};
+
+/// Receives the events intercepted by a [`Wrapper`](crate::Wrapper).
+pub trait Sink: Send + Sync {
+    fn event(&self, event: &Event<'_>);
+}
+
+impl<S> Sink for &'static S
+where
+    S: ?Sized + Sink,
+{
+    #[inline]
+    fn event(&self, event: &Event<'_>) {
+        (**self).event(event)
+    }
+}
+
+impl<S> Sink for Box<S>
+where
+    S: ?Sized + Sink,
+{
+    #[inline]
+    fn event(&self, event: &Event<'_>) {
+        (**self).event(event)
+    }
+}
+
+impl<S> Sink for Arc<S>
+where
+    S: ?Sized + Sink,
+{
+    #[inline]
+    fn event(&self, event: &Event<'_>) {
+        (**self).event(event)
+    }
+}
+
+/// Sends the events to both sinks.
+impl<A, B> Sink for (A, B)
+where
+    A: Sink,
+    B: Sink,
+{
+    #[inline]
+    fn event(&self, event: &Event<'_>) {
+        self.0.event(event);
+        self.1.event(event);
+    }
+}
+
+/// Writes events to the `log` crate, indented by depth.
+///
+/// Logs at the trace level to the `serde_log` target with values cut at 64 characters or bytes
+/// by default, see [`Config`](crate::Config) to change that. The events go to a sub-target named
+/// after the innermost struct or enum they are nested in, e.g. `serde_log::Server`, or else after
+/// the type (de)serialized at the top level, so `RUST_LOG` can scope tracing to a single type.
+#[derive(Clone, Debug)]
+pub struct LogSink {
+    level: log::Level,
+    target: Cow<'static, str>,
+    max_value_len: Option<usize>,
+}
+
+impl LogSink {
+    pub fn new<S>(level: log::Level, target: S, max_value_len: Option<usize>) -> Self
+    where
+        S: Into<Cow<'static, str>>,
+    {
+        Self {
+            level,
+            target: target.into(),
+            max_value_len,
+        }
+    }
+}
+
+impl Default for LogSink {
+    fn default() -> Self {
+        Self::new(log::Level::Trace, "serde_log", Some(crate::MAX_VALUE_LEN))
+    }
+}
+
+impl Sink for LogSink {
+    fn event(&self, event: &Event<'_>) {
+        if cfg!(feature = "disabled") || self.level > log::max_level() {
+            return;
+        }
+        let scoped;
+        let target = match event.scope {
+            Some(scope) => {
+                scoped = format!("{}::{}", self.target, scope);
+                &scoped
+            }
+            None => &*self.target,
+        };
+        let line = Truncated(Line(event), self.max_value_len);
+        log::log!(target: target, self.level, "{}", line);
+    }
+}
+
+/// Emits events as `tracing` events, nested under the spans of the `deserialize_*` calls.
+///
+/// With the `tracing` feature the entry points and [`Config`](crate::Config) send the events
+/// here at the trace level as well as to a [`LogSink`], with values cut at the same length.
+#[cfg(feature = "tracing")]
+#[derive(Clone, Copy, Debug)]
+pub struct TracingSink {
+    max_value_len: Option<usize>,
+}
+
+#[cfg(feature = "tracing")]
+impl TracingSink {
+    pub fn new(max_value_len: Option<usize>) -> Self {
+        Self { max_value_len }
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Default for TracingSink {
+    fn default() -> Self {
+        Self::new(Some(crate::MAX_VALUE_LEN))
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Sink for TracingSink {
+    fn event(&self, event: &Event<'_>) {
+        tracing::trace!(
+            target: "serde_log",
+            kind = event.kind.as_str(),
+            method = event.method,
+            scope = event.scope,
+            depth = event.depth,
+            path = %event.path,
+            "{}",
+            Truncated(event, self.max_value_len)
+        );
+    }
+}
+
+/// Collects every event as a [`Record`].
+#[derive(Debug, Default)]
+pub struct Collector {
+    records: Mutex<Vec<Record>>,
+}
+
+impl Collector {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn records(&self) -> Vec<Record> {
+        self.records.lock().unwrap().clone()
+    }
+
+    pub fn take(&self) -> Vec<Record> {
+        std::mem::take(&mut *self.records.lock().unwrap())
+    }
+
+    pub fn into_records(self) -> Vec<Record> {
+        self.records.into_inner().unwrap()
+    }
+}
+
+impl Sink for Collector {
+    fn event(&self, event: &Event<'_>) {
+        self.records.lock().unwrap().push(event.to_record());
+    }
+}
+
+/// A sink calling a closure for every event, see [`from_fn`].
+#[derive(Clone, Copy)]
+pub struct FromFn<F>(F);
+
+pub fn from_fn<F>(f: F) -> FromFn<F>
+where
+    F: Fn(&Event<'_>) + Send + Sync,
+{
+    FromFn(f)
+}
+
+impl<F> Sink for FromFn<F>
+where
+    F: Fn(&Event<'_>) + Send + Sync,
+{
+    #[inline]
+    fn event(&self, event: &Event<'_>) {
+        (self.0)(event)
+    }
+}
+
+impl<F> fmt::Debug for FromFn<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromFn").finish()
+    }
+}
+
+/// Renders an event as an indented log line.
+pub(crate) struct Line<'a>(pub(crate) &'a Event<'a>);
+
+impl fmt::Display for Line<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:indent$}", "", indent = self.0.depth as usize * 2)?;
+        fmt::Display::fmt(self.0, f)?;
+        write!(f, " at {}", self.0.path)
+    }
+}
+
+/// Renders a value or event with its strings and bytes cut at the given length, if any.
+pub(crate) struct Truncated<T>(pub(crate) T, pub(crate) Option<usize>);
+
+impl<T> fmt::Display for Truncated<T>
+where
+    T: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.1 {
+            Some(max) => write!(f, "{:.*}", max, self.0),
+            None => write!(f, "{}", self.0),
+        }
+    }
+}
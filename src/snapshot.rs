@@ -0,0 +1,195 @@
+//! Support for [`assert_trace_snapshot!`](crate::assert_trace_snapshot).
+
+use crate::{sink::Line, Trace};
+use serde::de;
+use std::{cmp, env, fmt::Write as _, fs, io, path::PathBuf};
+
+/// Set to anything but `0` to write the snapshots instead of comparing against them.
+const UPDATE_VAR: &str = "SERDE_LOG_UPDATE_SNAPSHOTS";
+
+/// Lines kept around the differences.
+const CONTEXT_LINES: usize = 3;
+
+/// Records the calls made deserializing a `T` and compares them against the golden file
+/// `tests/snapshots/<name>.snap` of the crate under test, returning the result of the
+/// deserialization.
+///
+/// The file holds one line per event, like [`Trace`](crate::Trace) displays them but with the
+/// type names stripped of their paths, which change across compiler and serde versions. Run the
+/// tests with `SERDE_LOG_UPDATE_SNAPSHOTS=1` to create or update it.
+///
+/// ```no_run
+/// let mut json = serde_json::Deserializer::from_str(r#"{"port": 8080}"#);
+/// let value: std::collections::HashMap<String, u16> =
+///     serde_log::assert_trace_snapshot!("port", &mut json).unwrap();
+/// ```
+#[macro_export]
+macro_rules! assert_trace_snapshot {
+    ($name:expr, $deserializer:expr $(,)?) => {
+        $crate::snapshot::assert_trace_snapshot(
+            ::std::path::Path::new(::std::env!("CARGO_MANIFEST_DIR"))
+                .join("tests")
+                .join("snapshots"),
+            $name,
+            $deserializer,
+        )
+    };
+}
+
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_trace_snapshot<'de, D, T>(
+    dir: PathBuf,
+    name: &str,
+    deserializer: D,
+) -> Result<T, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: de::Deserialize<'de>,
+{
+    let (result, trace) = crate::record(deserializer);
+    let actual = render(&trace);
+    let path = dir.join(format!("{}.snap", name));
+
+    if env::var_os(UPDATE_VAR).is_some_and(|v| v != "0") {
+        let unchanged = fs::read_to_string(&path).is_ok_and(|expected| expected == actual);
+        if !unchanged {
+            fs::create_dir_all(&dir)
+                .and_then(|_| fs::write(&path, &actual))
+                .unwrap_or_else(|e| panic!("writing {}: {}", path.display(), e));
+        }
+        return result;
+    }
+
+    let expected = match fs::read_to_string(&path) {
+        // Checkouts may have turned the line endings into CRLF.
+        Ok(expected) => expected.replace("\r\n", "\n"),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => panic!(
+            "missing trace snapshot {}, run with {}=1 to create it",
+            path.display(),
+            UPDATE_VAR
+        ),
+        Err(e) => panic!("reading {}: {}", path.display(), e),
+    };
+    if expected != actual {
+        panic!(
+            "trace snapshot {} does not match, run with {}=1 to update it\n\n{}",
+            path.display(),
+            UPDATE_VAR,
+            diff(&expected, &actual)
+        );
+    }
+    result
+}
+
+fn render(trace: &Trace) -> String {
+    let mut out = String::new();
+    for record in trace {
+        record.with_event(|event| {
+            let type_name = event.type_name.map(short_type_name);
+            let mut event = *event;
+            event.type_name = type_name.as_deref();
+            let _ = writeln!(out, "{}", Line(&event));
+        });
+    }
+    out
+}
+
+/// Strips the paths from the type names, e.g. `alloc::vec::Vec<alloc::string::String>` becomes
+/// `Vec<String>`, and the field identifiers generated by serde in
+/// `my_crate::_::<impl serde::de::Deserialize<'_> for my_crate::Config>::deserialize::__Field`
+/// become `__Field`.
+fn short_type_name(name: &str) -> String {
+    let mut out = String::new();
+    // Where the path being written started, at each nesting level.
+    let mut starts = vec![0];
+    let mut chars = name.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ':' if chars.peek() == Some(&':') => {
+                chars.next();
+                out.truncate(*starts.last().unwrap());
+            }
+            '<' | '(' | '[' => {
+                out.push(c);
+                starts.push(out.len());
+            }
+            '>' | ')' | ']' => {
+                out.push(c);
+                if starts.len() > 1 {
+                    starts.pop();
+                }
+            }
+            ',' | ' ' | ';' | '&' | '*' => {
+                out.push(c);
+                *starts.last_mut().unwrap() = out.len();
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// The lines removed from `expected` with a `-` and added in `actual` with a `+`.
+fn diff(expected: &str, actual: &str) -> String {
+    let old: Vec<_> = expected.lines().collect();
+    let new: Vec<_> = actual.lines().collect();
+    // Only what is in between the common prefix and suffix goes through the LCS table, traces
+    // can be long but their changes rarely are.
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                cmp::max(lcs[i + 1][j], lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<(char, &str)> = old[..prefix].iter().map(|l| (' ', *l)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push((' ', a[i]));
+            i += 1;
+            j += 1;
+        } else if j == b.len() || (i < a.len() && lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(('-', a[i]));
+            i += 1;
+        } else {
+            lines.push(('+', b[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[old.len() - suffix..].iter().map(|l| (' ', *l)));
+
+    let mut out = String::new();
+    let mut skipped = false;
+    for (n, (tag, line)) in lines.iter().enumerate() {
+        let lo = n.saturating_sub(CONTEXT_LINES);
+        let hi = cmp::min(n + CONTEXT_LINES + 1, lines.len());
+        if lines[lo..hi].iter().all(|(tag, _)| *tag == ' ') {
+            skipped = true;
+            continue;
+        }
+        if skipped {
+            out.push_str("...\n");
+            skipped = false;
+        }
+        let _ = writeln!(out, "{}{}", tag, line);
+    }
+    out
+}
@@ -0,0 +1,84 @@
+use crate::Path;
+
+/// A `tracing` span entered for the duration of a `deserialize_*` call.
+///
+/// Without the `tracing` feature this is a no-op.
+#[must_use]
+pub(crate) struct Span {
+    #[cfg(feature = "tracing")]
+    inner: tracing::span::EnteredSpan,
+}
+
+#[cfg(feature = "tracing")]
+impl Span {
+    #[inline]
+    pub(crate) fn enter(method: &'static str, depth: u32, path: &Path) -> Self {
+        let span = tracing::trace_span!(
+            target: "serde_log",
+            "deserialize",
+            method,
+            depth,
+            path = %path,
+            name = tracing::field::Empty,
+            fields = tracing::field::Empty,
+            variants = tracing::field::Empty,
+            len = tracing::field::Empty,
+        );
+        Self {
+            inner: span.entered(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn name(self, name: &str) -> Self {
+        self.inner.record("name", name);
+        self
+    }
+
+    #[inline]
+    pub(crate) fn fields(self, fields: &[&str]) -> Self {
+        self.inner.record("fields", tracing::field::debug(fields));
+        self
+    }
+
+    #[inline]
+    pub(crate) fn variants(self, variants: &[&str]) -> Self {
+        self.inner
+            .record("variants", tracing::field::debug(variants));
+        self
+    }
+
+    #[inline]
+    pub(crate) fn len(self, len: usize) -> Self {
+        self.inner.record("len", len);
+        self
+    }
+}
+
+#[cfg(not(feature = "tracing"))]
+impl Span {
+    #[inline(always)]
+    pub(crate) fn enter(_method: &'static str, _depth: u32, _path: &Path) -> Self {
+        Self {}
+    }
+
+    #[inline(always)]
+    pub(crate) fn name(self, _name: &str) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub(crate) fn fields(self, _fields: &[&str]) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub(crate) fn variants(self, _variants: &[&str]) -> Self {
+        self
+    }
+
+    #[inline(always)]
+    pub(crate) fn len(self, _len: usize) -> Self {
+        self
+    }
+}
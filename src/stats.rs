@@ -0,0 +1,111 @@
+use crate::{Event, Kind, Outcome, Value};
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+/// A summary of a whole deserialization, see
+/// [`deserialize_with_stats`](crate::deserialize_with_stats) and
+/// [`Config::stats`](crate::Config::stats).
+#[derive(Clone, Debug, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Stats {
+    /// How many times each method was called.
+    pub calls: BTreeMap<String, usize>,
+    pub max_depth: u32,
+    /// The elements read from sequences.
+    pub elements: usize,
+    /// The keys read from maps, including struct fields.
+    pub keys: usize,
+    /// The length of the strings and bytes visited.
+    pub payload_bytes: usize,
+    /// The strings and bytes visited borrowed from the input.
+    pub borrowed: usize,
+    /// The strings and bytes visited as a temporary or owned copy.
+    pub owned: usize,
+    pub elapsed: Duration,
+    pub outcome: Outcome<'static>,
+}
+
+impl Stats {
+    /// The total number of calls.
+    pub fn total_calls(&self) -> usize {
+        self.calls.values().sum()
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} in {:.1?}: {} calls (",
+            self.outcome,
+            self.elapsed,
+            self.total_calls()
+        )?;
+        for (i, (method, count)) in self.calls.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}={}", method, count)?;
+        }
+        write!(
+            f,
+            "), max depth {}, {} elements, {} keys, {} payload bytes ({} borrowed, {} owned)",
+            self.max_depth, self.elements, self.keys, self.payload_bytes, self.borrowed, self.owned
+        )
+    }
+}
+
+/// Builds [`Stats`] out of the events as they are emitted.
+#[derive(Debug, Default)]
+pub(crate) struct Tally {
+    stats: Stats,
+    /// The depth of the `SeqAccess` or `MapAccess` call waiting to see if an element or key
+    /// gets read below it.
+    pending: Option<(Kind, u32)>,
+}
+
+impl Tally {
+    pub(crate) fn add(&mut self, event: &Event<'_>) {
+        let stats = &mut self.stats;
+        stats.max_depth = stats.max_depth.max(event.depth);
+        match self.pending.take() {
+            Some((Kind::SeqAccess, depth)) if event.depth > depth => stats.elements += 1,
+            Some((_, depth)) if event.depth > depth => stats.keys += 1,
+            _ => {}
+        }
+        // Every deserializer call ends with an exit event, other events are not calls.
+        if event.is_exit() {
+            *stats.calls.entry(event.method.to_owned()).or_default() += 1;
+            return;
+        }
+        match (event.kind, event.method) {
+            (Kind::SeqAccess, "next_element" | "next_element_seed") => {
+                self.pending = Some((event.kind, event.depth))
+            }
+            (Kind::MapAccess, "next_key" | "next_key_seed" | "next_entry" | "next_entry_seed") => {
+                self.pending = Some((event.kind, event.depth))
+            }
+            (Kind::Visitor, method) => {
+                match event.value {
+                    Some(Value::Str(v)) => stats.payload_bytes += v.len(),
+                    Some(Value::Bytes(v)) => stats.payload_bytes += v.len(),
+                    _ => {}
+                }
+                match method {
+                    "visit_borrowed_str" | "visit_borrowed_bytes" => stats.borrowed += 1,
+                    "visit_str" | "visit_string" | "visit_bytes" | "visit_byte_buf" => {
+                        stats.owned += 1
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub(crate) fn finish(&mut self, outcome: Outcome<'static>, elapsed: Duration) -> Stats {
+        let mut stats = std::mem::take(&mut self.stats);
+        stats.outcome = outcome;
+        stats.elapsed = elapsed;
+        stats
+    }
+}
@@ -0,0 +1,284 @@
+use crate::{
+    replay::{Access, ReplayDeserializer},
+    Kind, ReplayError, Trace, Value,
+};
+use serde::de::Error as _;
+use serde_test::Token;
+use std::{convert::TryFrom, fmt::Write as _};
+
+impl Trace {
+    /// The [`serde_test`] tokens of the values visited in the trace, to check that the types
+    /// deserialize the same way without the format it was recorded from.
+    ///
+    /// The names and the values are leaked to get `'static` tokens, this is meant for tests.
+    ///
+    /// ```
+    /// # #[cfg(not(feature = "disabled"))]
+    /// # {
+    /// use serde_test::Token;
+    ///
+    /// let (_, trace) =
+    ///     serde_log::record::<_, Vec<u32>>(&mut serde_json::Deserializer::from_str("[1, 2]"));
+    /// assert_eq!(
+    ///     trace.to_tokens().unwrap(),
+    ///     [Token::Seq { len: Some(2) }, Token::U64(1), Token::U64(2), Token::SeqEnd],
+    /// );
+    /// # }
+    /// ```
+    pub fn to_tokens(&self) -> Result<Vec<Token>, ReplayError> {
+        let mut tokens = Vec::new();
+        ReplayDeserializer::new(self).tokens(&mut tokens)?;
+        Ok(tokens)
+    }
+
+    /// The tokens of [`to_tokens`](Self::to_tokens) as a Rust slice, to paste in a test.
+    pub fn to_tokens_source(&self) -> Result<String, ReplayError> {
+        let mut out = String::from("&[\n");
+        for token in self.to_tokens()? {
+            // The slices would be printed as arrays.
+            let _ = match token {
+                Token::Bytes(v) => writeln!(out, "    Token::Bytes(&{:?}),", v),
+                Token::BorrowedBytes(v) => writeln!(out, "    Token::BorrowedBytes(&{:?}),", v),
+                Token::ByteBuf(v) => writeln!(out, "    Token::ByteBuf(&{:?}),", v),
+                token => writeln!(out, "    Token::{:?},", token),
+            };
+        }
+        out.push(']');
+        Ok(out)
+    }
+}
+
+impl ReplayDeserializer<'_> {
+    /// Moves past the next visitor call, pushing the tokens of what it visited.
+    fn tokens(&mut self, out: &mut Vec<Token>) -> Result<(), ReplayError> {
+        let start = self.pos;
+        let index = self.next_visit()?;
+        let records = self.records;
+        let record = &records[index];
+        // The call the visitor was given to, telling the type deserialized.
+        let hint = records[start..index].iter().rev().find(|r| {
+            r.kind == Kind::Deserializer && !r.is_exit() && r.method.starts_with("deserialize_")
+        });
+        let hint_method = hint.map_or("", |h| &h.method);
+        let arg = |name: &str| {
+            hint.and_then(|h| h.args.iter().find(|(n, _)| n == name))
+                .map(|(_, v)| v)
+        };
+        let name = match arg("name") {
+            Some(Value::Str(v)) => leak(v),
+            _ => "",
+        };
+        let depth = record.depth + 1;
+        match (record.method.as_ref(), &record.value) {
+            (_, Some(Value::Bool(v))) => out.push(Token::Bool(*v)),
+            (_, Some(Value::I8(v))) => out.push(Token::I8(*v)),
+            (_, Some(Value::I16(v))) => out.push(Token::I16(*v)),
+            (_, Some(Value::I32(v))) => out.push(Token::I32(*v)),
+            (_, Some(Value::I64(v))) => out.push(Token::I64(*v)),
+            (_, Some(Value::U8(v))) => out.push(Token::U8(*v)),
+            (_, Some(Value::U16(v))) => out.push(Token::U16(*v)),
+            (_, Some(Value::U32(v))) => out.push(Token::U32(*v)),
+            (_, Some(Value::U64(v))) => out.push(Token::U64(*v)),
+            (_, Some(Value::F32(v))) => out.push(Token::F32(*v)),
+            (_, Some(Value::F64(v))) => out.push(Token::F64(*v)),
+            (_, Some(Value::Char(v))) => out.push(Token::Char(*v)),
+            ("visit_borrowed_str", Some(Value::Str(v))) => out.push(Token::BorrowedStr(leak(v))),
+            ("visit_string", Some(Value::Str(v))) => out.push(Token::String(leak(v))),
+            (_, Some(Value::Str(v))) => out.push(Token::Str(leak(v))),
+            ("visit_borrowed_bytes", Some(Value::Bytes(v))) => {
+                out.push(Token::BorrowedBytes(leak_bytes(v)))
+            }
+            ("visit_byte_buf", Some(Value::Bytes(v))) => out.push(Token::ByteBuf(leak_bytes(v))),
+            (_, Some(Value::Bytes(v))) => out.push(Token::Bytes(leak_bytes(v))),
+            (_, Some(Value::I128(_) | Value::U128(_))) => {
+                return Err(ReplayError::custom(format!(
+                    "the value at {} has no token",
+                    record.path
+                )))
+            }
+            (_, Some(Value::Redacted(_))) => {
+                return Err(ReplayError::custom(format!(
+                    "the value at {} was redacted when recorded",
+                    record.path
+                )))
+            }
+            ("visit_none", _) => out.push(Token::None),
+            ("visit_unit", _) if hint_method == "deserialize_unit_struct" => {
+                out.push(Token::UnitStruct { name })
+            }
+            ("visit_unit", _) => out.push(Token::Unit),
+            ("visit_some", _) => {
+                out.push(Token::Some);
+                self.tokens(out)?;
+            }
+            ("visit_newtype_struct", _) => {
+                out.push(Token::NewtypeStruct { name });
+                self.tokens(out)?;
+            }
+            ("visit_seq", _) => {
+                let (len, elements) = self.seq(depth)?;
+                let (begin, end) = match hint_method {
+                    "deserialize_tuple" => (Token::Tuple { len }, Token::TupleEnd),
+                    "deserialize_tuple_struct" => {
+                        (Token::TupleStruct { name, len }, Token::TupleStructEnd)
+                    }
+                    _ => (Token::Seq { len: Some(len) }, Token::SeqEnd),
+                };
+                out.push(begin);
+                out.extend(elements);
+                out.push(end);
+            }
+            ("visit_map", _) => {
+                let (len, entries) = self.map(depth)?;
+                let (begin, end) = match hint_method {
+                    "deserialize_struct" => (Token::Struct { name, len }, Token::StructEnd),
+                    _ => (Token::Map { len: Some(len) }, Token::MapEnd),
+                };
+                out.push(begin);
+                out.extend(entries);
+                out.push(end);
+            }
+            ("visit_enum", _) => {
+                let variants = match arg("variants") {
+                    Some(Value::Seq(v)) => v.as_slice(),
+                    _ => &[],
+                };
+                self.variant(depth, name, variants, out)?;
+            }
+            (method, _) => {
+                return Err(ReplayError::custom(format!(
+                    "cannot convert `{}` to tokens",
+                    method
+                )))
+            }
+        }
+        self.skip_call(index);
+        Ok(())
+    }
+
+    /// The number of elements of the `SeqAccess` at `depth` and their tokens.
+    fn seq(&mut self, depth: u32) -> Result<(usize, Vec<Token>), ReplayError> {
+        let mut access = Access::new(self, depth);
+        let mut tokens = Vec::new();
+        let mut len = 0;
+        while access.next_element(|de| de.tokens(&mut tokens))?.is_some() {
+            len += 1;
+        }
+        Ok((len, tokens))
+    }
+
+    /// The number of entries of the `MapAccess` at `depth` and their tokens.
+    fn map(&mut self, depth: u32) -> Result<(usize, Vec<Token>), ReplayError> {
+        let mut access = Access::new(self, depth);
+        let mut tokens = Vec::new();
+        let mut len = 0;
+        while access.next_key(|de| de.tokens(&mut tokens))?.is_some() {
+            access.next_value(|de| de.tokens(&mut tokens))?;
+            len += 1;
+        }
+        Ok((len, tokens))
+    }
+
+    /// Pushes the tokens of the variant read by the `EnumAccess` at `depth`.
+    fn variant(
+        &mut self,
+        depth: u32,
+        name: &'static str,
+        variants: &[Value<'_>],
+        out: &mut Vec<Token>,
+    ) -> Result<(), ReplayError> {
+        let records = self.records;
+        let mut access = Access::new(self, depth);
+        let mut identifier = Vec::new();
+        access.next_variant(|de| de.tokens(&mut identifier))?;
+        // The tokens name the variants, those read by index are looked up in the hint.
+        let index = match identifier.as_slice() {
+            [Token::U8(v)] => Some(u64::from(*v)),
+            [Token::U16(v)] => Some(u64::from(*v)),
+            [Token::U32(v)] => Some(u64::from(*v)),
+            [Token::U64(v)] => Some(*v),
+            _ => None,
+        };
+        let variant = match (identifier.as_slice(), index) {
+            ([Token::Str(v) | Token::BorrowedStr(v) | Token::String(v)], _) => *v,
+            (_, Some(i)) => match usize::try_from(i).ok().and_then(|i| variants.get(i)) {
+                Some(Value::Str(v)) => leak(v),
+                _ => return Err(ReplayError::custom(format!("unknown variant index {}", i))),
+            },
+            _ => return Err(ReplayError::custom("expected a variant identifier")),
+        };
+
+        let index = access.expect(Kind::VariantAccess, "a variant")?;
+        match records[index].method.as_ref() {
+            "unit_variant" => {
+                out.push(Token::UnitVariant { name, variant });
+                access.skip_call(index);
+            }
+            "newtype_variant_seed" | "newtype_variant" => {
+                out.push(Token::NewtypeVariant { name, variant });
+                access.nested(index, "a newtype variant", |de| de.tokens(out))?;
+            }
+            method @ ("tuple_variant" | "struct_variant") => {
+                let (seq, len, tokens) = access.nested(index, "a variant", |de| de.collection())?;
+                let (begin, end) = match (method, seq) {
+                    ("tuple_variant", true) => (
+                        Token::TupleVariant { name, variant, len },
+                        Token::TupleVariantEnd,
+                    ),
+                    ("struct_variant", false) => (
+                        Token::StructVariant { name, variant, len },
+                        Token::StructVariantEnd,
+                    ),
+                    // Formats like bincode read struct variants as sequences, which the tokens
+                    // only allow after the variant identifier.
+                    (_, seq) => {
+                        out.push(Token::Enum { name });
+                        out.extend(identifier);
+                        match seq {
+                            true => (Token::Seq { len: Some(len) }, Token::SeqEnd),
+                            false => (Token::Map { len: Some(len) }, Token::MapEnd),
+                        }
+                    }
+                };
+                out.push(begin);
+                out.extend(tokens);
+                out.push(end);
+            }
+            method => {
+                return Err(ReplayError::custom(format!(
+                    "cannot convert `{}` to tokens",
+                    method
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves past the next visitor call, returning whether it visited a sequence or a map, the
+    /// number of elements or entries and their tokens.
+    fn collection(&mut self) -> Result<(bool, usize, Vec<Token>), ReplayError> {
+        let index = self.next_visit()?;
+        let record = &self.records[index];
+        let depth = record.depth + 1;
+        let (seq, (len, tokens)) = match record.method.as_ref() {
+            "visit_seq" => (true, self.seq(depth)?),
+            "visit_map" => (false, self.map(depth)?),
+            method => {
+                return Err(ReplayError::custom(format!(
+                    "expected a sequence or a map, found `{}`",
+                    method
+                )))
+            }
+        };
+        self.skip_call(index);
+        Ok((seq, len, tokens))
+    }
+}
+
+fn leak(s: &str) -> &'static str {
+    Box::leak(s.into())
+}
+
+fn leak_bytes(b: &[u8]) -> &'static [u8] {
+    Box::leak(b.into())
+}
@@ -0,0 +1,122 @@
+use crate::{sink::Line, Kind, Record};
+use serde::{de, ser, Deserialize, Serialize};
+use std::{fmt, slice};
+
+/// The events recorded while going through a [`Wrapper`](crate::Wrapper), see
+/// [`record`](crate::record).
+///
+/// Traces can be saved with any serde format, and replayed later on with a
+/// [`ReplayDeserializer`](crate::ReplayDeserializer).
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trace {
+    records: Vec<Record>,
+}
+
+impl Trace {
+    #[inline]
+    pub fn records(&self) -> &[Record] {
+        &self.records
+    }
+
+    #[inline]
+    pub fn into_records(self) -> Vec<Record> {
+        self.records
+    }
+
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<'_, Record> {
+        self.records.iter()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// The records of the given kind, in call order.
+    pub fn of_kind(&self, kind: Kind) -> impl Iterator<Item = &Record> {
+        self.records.iter().filter(move |r| r.kind == kind)
+    }
+
+    /// The names of the methods called, in call order.
+    pub fn methods(&self) -> impl Iterator<Item = &str> {
+        self.records
+            .iter()
+            .filter(|r| !r.is_exit())
+            .map(|r| r.method.as_ref())
+    }
+
+    /// How many times `method` was called.
+    pub fn count(&self, method: &str) -> usize {
+        self.methods().filter(|m| *m == method).count()
+    }
+
+    #[inline]
+    pub fn contains(&self, method: &str) -> bool {
+        self.methods().any(|m| m == method)
+    }
+}
+
+impl From<Vec<Record>> for Trace {
+    #[inline]
+    fn from(records: Vec<Record>) -> Self {
+        Self { records }
+    }
+}
+
+/// Traces are (de)serialized as the sequence of their records.
+impl Serialize for Trace {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        self.records.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Trace {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        Vec::deserialize(deserializer).map(Trace::from)
+    }
+}
+
+impl<'a> IntoIterator for &'a Trace {
+    type Item = &'a Record;
+    type IntoIter = slice::Iter<'a, Record>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Trace {
+    type Item = Record;
+    type IntoIter = std::vec::IntoIter<Record>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.records.into_iter()
+    }
+}
+
+/// One line per record, the precision, if any, truncates the values like in the logs.
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.records {
+            record.with_event(|event| fmt::Display::fmt(&Line(event), f))?;
+            f.write_str("\n")?;
+        }
+        Ok(())
+    }
+}
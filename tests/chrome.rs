@@ -0,0 +1,112 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::Deserialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+enum Shape {
+    Empty,
+    Circle(f64),
+    Rect { w: f64, h: f64 },
+}
+
+/// The events written deserializing `json` as a `T`.
+fn events<'de, T>(json: &'de str) -> Vec<Value>
+where
+    T: Deserialize<'de>,
+{
+    events_with::<T>(serde_log::ChromeTrace::new(Vec::new()), json)
+}
+
+fn events_with<'de, T>(chrome: serde_log::ChromeTrace<Vec<u8>>, json: &'de str) -> Vec<Value>
+where
+    T: Deserialize<'de>,
+{
+    let chrome = Arc::new(chrome);
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let wrapper = serde_log::Deserializer::with_sink(&mut deserializer, chrome.clone());
+    T::deserialize(wrapper).unwrap();
+    let out = Arc::try_unwrap(chrome).unwrap().into_inner().unwrap();
+    serde_json::from_slice(&out).unwrap()
+}
+
+fn phases(events: &[Value]) -> Vec<&str> {
+    events.iter().map(|e| e["ph"].as_str().unwrap()).collect()
+}
+
+#[test]
+fn balanced_durations() {
+    let events = events::<Vec<Shape>>(r#"["Empty", {"Circle": 1.0}, {"Rect": {"w": 1, "h": 2}}]"#);
+    let phases = phases(&events);
+    let begins = phases.iter().filter(|p| **p == "B").count();
+    let ends = phases.iter().filter(|p| **p == "E").count();
+    assert!(begins > 0);
+    assert_eq!(begins, ends);
+
+    // Every prefix has at least as many begins as ends.
+    let mut open = 0i32;
+    for phase in &phases {
+        match *phase {
+            "B" => open += 1,
+            "E" => open -= 1,
+            _ => {}
+        }
+        assert!(open >= 0);
+    }
+
+    let unit_variant = events
+        .iter()
+        .filter(|e| e["args"]["method"] == "unit_variant")
+        .map(|e| e["ph"].as_str().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(unit_variant, ["B", "E"]);
+}
+
+#[test]
+fn visitor_instants() {
+    let events = events::<Vec<u32>>("[7]");
+    let visits: Vec<_> = events
+        .iter()
+        .filter(|e| e["cat"] == "Visitor")
+        .map(|e| {
+            (
+                e["ph"].as_str().unwrap(),
+                e["args"]["method"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(visits, [("i", "visit_seq"), ("i", "visit_u64")]);
+    assert!(events
+        .iter()
+        .any(|e| e["args"]["method"] == "visit_u64" && e["args"]["value"] == "7"));
+}
+
+#[test]
+fn into_inner_finishes() {
+    let chrome = serde_log::ChromeTrace::new(Vec::new());
+    assert_eq!(chrome.into_inner().unwrap(), b"[]\n");
+}
+
+#[test]
+fn truncated_values() {
+    let visited = |chrome, s: &str| {
+        let json = serde_json::to_string(s).unwrap();
+        let events = events_with::<String>(chrome, &json);
+        events
+            .iter()
+            .find_map(|e| e["args"]["value"].as_str().map(str::to_owned))
+            .unwrap()
+    };
+
+    let long = "x".repeat(100);
+    assert_eq!(
+        visited(serde_log::ChromeTrace::new(Vec::new()), &long),
+        format!("{:?}…(100 bytes)", &long[..64])
+    );
+    let chrome = serde_log::ChromeTrace::new(Vec::new()).max_value_len(Some(2));
+    assert_eq!(visited(chrome, "abcd"), r#""ab"…(4 bytes)"#);
+    let chrome = serde_log::ChromeTrace::new(Vec::new()).max_value_len(None);
+    assert_eq!(visited(chrome, &long), format!("{:?}", long));
+}
@@ -0,0 +1,167 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::Deserialize;
+use serde_log::{Config, Kind, Record};
+use std::sync::Mutex;
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Server {
+    host: String,
+    ports: Vec<u16>,
+}
+
+const JSON: &str = r#"[{"host": "local", "ports": [80, 443]}]"#;
+
+/// An event without its timing: its kind, method, depth, path and whether it is an exit.
+type Key = (Kind, String, u32, String, bool);
+
+/// The events recorded with `config`.
+fn events(config: Config) -> Vec<Key> {
+    let (result, trace) =
+        config.record::<_, Vec<Server>>(&mut serde_json::Deserializer::from_str(JSON));
+    assert!(result.is_ok());
+    trace.iter().map(key).collect()
+}
+
+fn key(r: &Record) -> Key {
+    (
+        r.kind,
+        r.method.to_string(),
+        r.depth,
+        r.path.to_string(),
+        r.is_exit(),
+    )
+}
+
+/// Checks `config` keeps exactly the events of the default config matching `keep`.
+fn assert_keeps<F>(config: Config, keep: F)
+where
+    F: Fn(&Key) -> bool,
+{
+    let all = events(Config::new());
+    let expected: Vec<_> = all.iter().filter(|e| keep(e)).cloned().collect();
+    assert!(!expected.is_empty());
+    assert!(expected.len() < all.len(), "nothing filtered out");
+    assert_eq!(events(config), expected);
+}
+
+#[test]
+fn max_depth() {
+    let max = events(Config::new()).iter().map(|e| e.2).max().unwrap();
+    assert!(max > 4);
+    assert_keeps(Config::new().max_depth(4), |e| e.2 <= 4);
+    assert_keeps(Config::new().max_depth(0), |e| e.2 == 0);
+}
+
+#[test]
+fn deserializer_calls() {
+    assert_keeps(Config::new().deserializer_calls(false), |e| {
+        !matches!(
+            e.0,
+            Kind::Deserialize | Kind::Deserializer | Kind::DeserializeSeed
+        )
+    });
+}
+
+#[test]
+fn visitor_calls() {
+    assert_keeps(Config::new().visitor_calls(false), |e| e.0 != Kind::Visitor);
+}
+
+#[test]
+fn access_calls() {
+    assert_keeps(Config::new().access_calls(false), |e| {
+        !matches!(
+            e.0,
+            Kind::SeqAccess | Kind::MapAccess | Kind::EnumAccess | Kind::VariantAccess
+        )
+    });
+}
+
+#[test]
+fn exit_events() {
+    assert_keeps(Config::new().exit_events(false), |e| !e.4);
+}
+
+#[test]
+fn combined() {
+    let config = Config::new()
+        .visitor_calls(false)
+        .access_calls(false)
+        .exit_events(false)
+        .max_depth(4);
+    let events = events(config);
+    assert!(!events.is_empty());
+    for (kind, method, depth, path, exit) in &events {
+        assert!(
+            matches!(
+                kind,
+                Kind::Deserialize | Kind::Deserializer | Kind::DeserializeSeed
+            ),
+            "{} at {}",
+            method,
+            path
+        );
+        assert!(*depth <= 4 && !exit, "{} at {}", method, path);
+    }
+}
+
+/// Keeps the level and message of every record.
+struct Capture(Mutex<Vec<(log::Level, String)>>);
+
+impl log::Log for Capture {
+    fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        let line = (record.level(), record.args().to_string());
+        self.0.lock().unwrap().push(line);
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+/// The lines logged deserializing `json` as a `String` with `config`.
+fn logged(config: Config, json: &str) -> Vec<(log::Level, String)> {
+    let value: String = config
+        .deserialize(&mut serde_json::Deserializer::from_str(json))
+        .unwrap();
+    assert_eq!(serde_json::to_string(&value).unwrap(), json);
+    std::mem::take(&mut *LOGGER.0.lock().unwrap())
+}
+
+// A single test as the logger is global.
+#[test]
+fn logging() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let lines = logged(Config::new(), r#""a""#);
+    assert!(!lines.is_empty());
+    assert!(lines.iter().all(|(level, _)| *level == log::Level::Trace));
+    let lines = logged(Config::new().level(log::Level::Debug), r#""a""#);
+    assert!(!lines.is_empty());
+    assert!(lines.iter().all(|(level, _)| *level == log::Level::Debug));
+
+    // Below the max level nothing is logged, and the value still comes through.
+    log::set_max_level(log::LevelFilter::Info);
+    assert!(logged(Config::new().level(log::Level::Debug), r#""a""#).is_empty());
+    assert!(!logged(Config::new().level(log::Level::Info), r#""a""#).is_empty());
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let long = "x".repeat(100);
+    let json = serde_json::to_string(&long).unwrap();
+    let contains =
+        |lines: &[(log::Level, String)], s: &str| lines.iter().any(|(_, line)| line.contains(s));
+    let lines = logged(Config::new(), &json);
+    assert!(contains(&lines, &format!("{:?}…(100 bytes)", &long[..64])));
+    let lines = logged(Config::new().max_value_len(Some(3)), &json);
+    assert!(contains(&lines, r#""xxx"…(100 bytes)"#));
+    let lines = logged(Config::new().max_value_len(None), &json);
+    assert!(contains(&lines, &json), "{:#?}", lines);
+    assert!(!contains(&lines, "…"), "{:#?}", lines);
+}
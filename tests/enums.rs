@@ -0,0 +1,119 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::Deserialize;
+use serde_log::{Kind, Record, Trace, Value};
+
+#[derive(Debug, PartialEq, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(u8),
+    Line(u8, u8),
+    Rect { w: u8 },
+}
+
+fn record(json: &str) -> (Vec<Shape>, Trace) {
+    let (result, trace) = serde_log::record(&mut serde_json::Deserializer::from_str(json));
+    (result.unwrap(), trace)
+}
+
+fn args(record: &Record) -> Vec<String> {
+    record
+        .args
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect()
+}
+
+#[test]
+fn variant_access() {
+    let json = r#"["Empty", {"Circle": 1}, {"Line": [2, 3]}, {"Rect": {"w": 4}}]"#;
+    let (shapes, trace) = record(json);
+    assert_eq!(
+        shapes,
+        [
+            Shape::Empty,
+            Shape::Circle(1),
+            Shape::Line(2, 3),
+            Shape::Rect { w: 4 }
+        ]
+    );
+
+    let calls: Vec<_> = trace
+        .of_kind(Kind::VariantAccess)
+        .filter(|r| !r.is_exit())
+        .map(|r| (r.method.as_ref(), r.path.to_string(), args(r)))
+        .collect();
+    assert_eq!(
+        calls,
+        [
+            ("unit_variant", "/0/Empty".to_owned(), vec![]),
+            ("newtype_variant", "/1/Circle".to_owned(), vec![]),
+            (
+                "tuple_variant",
+                "/2/Line".to_owned(),
+                vec!["len=2".to_owned()]
+            ),
+            (
+                "struct_variant",
+                "/3/Rect".to_owned(),
+                vec![r#"fields=["w"]"#.to_owned()]
+            ),
+        ]
+    );
+    // Every call returns.
+    assert_eq!(
+        trace
+            .of_kind(Kind::VariantAccess)
+            .filter(|r| r.is_exit())
+            .count(),
+        4
+    );
+    assert!(trace.contains("visit_enum"));
+    assert_eq!(trace.count("deserialize_enum"), 4);
+}
+
+#[test]
+fn payloads_are_nested() {
+    let json = r#"[{"Circle": 1}, {"Line": [2, 3]}, {"Rect": {"w": 4}}]"#;
+    let (_, trace) = record(json);
+    let records = trace.records();
+
+    // Each visited value is deeper than the variant access it belongs to.
+    let mut variant_depth = None;
+    let mut visits = Vec::new();
+    for record in records.iter().filter(|r| !r.is_exit()) {
+        if record.kind == Kind::VariantAccess {
+            variant_depth = Some(record.depth);
+        }
+        if let Some(Value::U64(v)) = record.value {
+            let depth = variant_depth.expect("a value outside of a variant");
+            assert!(record.depth > depth, "{} at {}", record, record.path);
+            visits.push((v, record.path.to_string()));
+        }
+    }
+    assert_eq!(
+        visits,
+        [
+            (1, "/0/Circle".to_owned()),
+            (2, "/1/Line/0".to_owned()),
+            (3, "/1/Line/1".to_owned()),
+            (4, "/2/Rect/w".to_owned()),
+        ]
+    );
+
+    // The payloads go through the wrapped seq and map access.
+    let line = records
+        .iter()
+        .position(|r| r.method == "tuple_variant")
+        .unwrap();
+    assert_eq!(records[line + 1].method, "visit_seq");
+    assert_eq!(records[line + 1].depth, records[line].depth + 1);
+    let rect = records
+        .iter()
+        .position(|r| r.method == "struct_variant")
+        .unwrap();
+    assert_eq!(records[rect + 1].method, "visit_map");
+    assert!(records[rect..]
+        .iter()
+        .any(|r| r.kind == Kind::MapAccess && r.method == "next_key"));
+}
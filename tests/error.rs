@@ -0,0 +1,114 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::Deserialize;
+use std::error::Error as _;
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    name: String,
+    servers: Vec<Server>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[test]
+fn context() {
+    let json =
+        r#"{"name": "app", "servers": [{"host": "a", "port": 80}, {"host": "b", "port": -1}]}"#;
+    let err = serde_log::deserialize_with_context::<_, Config>(
+        &mut serde_json::Deserializer::from_str(json),
+    )
+    .unwrap_err();
+
+    assert_eq!(err.path().to_string(), "/servers/1/port");
+    assert!(err
+        .to_string()
+        .starts_with("/servers/1/port: invalid value: integer `-1`"));
+
+    // The last events, oldest first, ending with the failing visit.
+    let events = err.events();
+    assert_eq!(events.len(), 16, "{:#?}", events);
+    let (last, rest) = events.split_last().unwrap();
+    assert!(last
+        .trim_start()
+        .starts_with("Visitor: visit_i64 -> u16 = Err(invalid value"));
+    assert!(last.ends_with(" at /servers/1/port"), "{}", last);
+    assert!(rest.last().unwrap().contains("Visitor: visit_i64(-1)"));
+    assert!(rest
+        .iter()
+        .any(|e| e.contains(r#"visit_borrowed_str("port")"#)));
+    // Nothing from the unwinding once failed.
+    assert!(rest.iter().all(|e| !e.contains("= Err(")), "{:#?}", events);
+
+    let source = err.source().unwrap();
+    assert_eq!(source.to_string(), err.inner().to_string());
+    assert!(source.downcast_ref::<serde_json::Error>().is_some());
+    assert!(err.into_inner().is_data());
+}
+
+#[test]
+fn ok() {
+    let json = r#"{"name": "app", "servers": []}"#;
+    let config: Config =
+        serde_log::deserialize_with_context(&mut serde_json::Deserializer::from_str(json)).unwrap();
+    assert_eq!(config.name, "app");
+}
+
+#[test]
+fn truncated_history() {
+    let host = "h".repeat(100);
+    let json = format!(r#"["{}", -1]"#, host);
+    let events = |config: serde_log::Config| {
+        let mut deserializer = serde_json::Deserializer::from_str(&json);
+        config
+            .deserialize_with_context::<_, (String, u8)>(&mut deserializer)
+            .unwrap_err()
+            .events()
+            .to_vec()
+    };
+
+    let default = events(serde_log::Config::new());
+    let cut = format!("{:?}…(100 bytes)", &host[..64]);
+    assert!(default.iter().any(|e| e.contains(&cut)), "{:#?}", default);
+
+    let short = events(serde_log::Config::new().max_value_len(Some(3)));
+    assert!(
+        short.iter().any(|e| e.contains(r#""hhh"…(100 bytes)"#)),
+        "{:#?}",
+        short
+    );
+
+    let whole = events(serde_log::Config::new().max_value_len(None));
+    let quoted = format!("{:?}", host);
+    assert!(whole.iter().any(|e| e.contains(&quoted)), "{:#?}", whole);
+    assert!(whole.iter().all(|e| !e.contains('…')), "{:#?}", whole);
+}
+
+#[test]
+fn context_events() {
+    let json = r#"{"name": "app", "servers": [{"host": "a", "port": -1}]}"#;
+    let events = |config: serde_log::Config| {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        config
+            .deserialize_with_context::<_, Config>(&mut deserializer)
+            .unwrap_err()
+            .events()
+            .to_vec()
+    };
+
+    let default = events(serde_log::Config::new());
+    assert_eq!(default.len(), 16);
+    // Fewer events keep the most recent ones, more keep older ones too.
+    let few = events(serde_log::Config::new().context_events(4));
+    assert_eq!(few, default[12..]);
+    let many = events(serde_log::Config::new().context_events(24));
+    assert_eq!(many.len(), 24);
+    assert_eq!(many[8..], default[..]);
+    assert!(events(serde_log::Config::new().context_events(0)).is_empty());
+}
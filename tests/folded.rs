@@ -0,0 +1,102 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::Deserialize;
+use serde_log::{FoldedStacks, Weight};
+use std::{collections::BTreeMap, sync::Arc};
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    name: String,
+    servers: Vec<Server>,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+const CONFIG: &str = r#"{"name": "app", "servers": [{"host": "local", "port": 80}]}"#;
+
+/// The folded stacks of deserializing `json` as a `T`.
+fn folded<'de, T>(json: &'de str, weight: Weight) -> String
+where
+    T: Deserialize<'de>,
+{
+    let stacks = Arc::new(FoldedStacks::new(weight));
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let wrapper = serde_log::Deserializer::with_sink(&mut deserializer, stacks.clone());
+    assert!(T::deserialize(wrapper).is_ok());
+    let mut out = Vec::new();
+    stacks.write_to(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn calls() {
+    assert_eq!(
+        folded::<Config>(CONFIG, Weight::Calls),
+        "\
+Config 13
+Config;name 3
+Config;servers 5
+Config;servers;0 1
+Config;servers;0;Server 13
+Config;servers;0;Server;host 3
+Config;servers;0;Server;port 3
+"
+    );
+}
+
+#[test]
+fn bytes() {
+    // The keys count towards the struct they are read in.
+    assert_eq!(
+        folded::<Config>(CONFIG, Weight::Bytes),
+        "\
+Config 11
+Config;name 3
+Config;servers;0;Server 8
+Config;servers;0;Server;host 5
+"
+    );
+}
+
+#[test]
+fn nanos() {
+    // The same stacks as the calls, less any a coarse clock timed as zero.
+    let calls = folded::<Config>(CONFIG, Weight::Calls);
+    let stacks: Vec<_> = calls
+        .lines()
+        .map(|l| l.rsplit_once(' ').unwrap().0)
+        .collect();
+    let out = folded::<Config>(CONFIG, Weight::Nanos);
+    for line in out.lines() {
+        let (stack, weight) = line.rsplit_once(' ').unwrap();
+        assert!(stacks.contains(&stack), "{}", line);
+        weight.parse::<u64>().unwrap();
+    }
+}
+
+#[test]
+fn no_root_frame() {
+    // Nothing names the sequence at the root, its own calls have no stack.
+    let out = folded::<Vec<Server>>(r#"[{"host": "a", "port": 1}]"#, Weight::Calls);
+    assert_eq!(
+        out,
+        "\
+0 1
+0;Server 13
+0;Server;host 3
+0;Server;port 3
+"
+    );
+}
+
+#[test]
+fn escaped_frames() {
+    let out = folded::<BTreeMap<String, u8>>(r#"{"a;b c": 1}"#, Weight::Calls);
+    assert_eq!(out, "a_b_c 3\n");
+}
@@ -0,0 +1,56 @@
+#![cfg(not(feature = "disabled"))]
+
+use std::sync::Mutex;
+
+/// Keeps every line logged.
+struct Capture(Mutex<Vec<String>>);
+
+impl log::Log for Capture {
+    fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        self.0.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+// A single test as the logger is global.
+#[test]
+fn reuses_buffer() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let mut place: Vec<u32> = Vec::with_capacity(16);
+    place.extend([7, 8, 9, 10]);
+    let (ptr, capacity) = (place.as_ptr(), place.capacity());
+
+    let mut json = serde_json::Deserializer::from_str("[1, 2, 3]");
+    serde_log::deserialize_in_place(&mut json, &mut place).unwrap();
+    assert_eq!(place, [1, 2, 3]);
+    assert_eq!(place.as_ptr(), ptr);
+    assert_eq!(place.capacity(), capacity);
+
+    let lines = std::mem::take(&mut *LOGGER.0.lock().unwrap());
+    let first = lines.first().unwrap();
+    assert!(
+        first.starts_with("Deserialize: deserialize_in_place -> alloc::vec::Vec<u32>"),
+        "{:#?}",
+        lines
+    );
+    // The elements go through the wrapper too.
+    assert!(lines.iter().any(|l| l.contains("deserialize_seq")));
+    assert_eq!(
+        lines
+            .iter()
+            .filter(|l| l.contains("visit_u64(") && l.ends_with(" at /2"))
+            .count(),
+        1,
+        "{:#?}",
+        lines
+    );
+}
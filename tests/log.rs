@@ -0,0 +1,71 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::Deserialize;
+use std::sync::Mutex;
+
+/// Keeps the target and message of every record.
+struct Capture(Mutex<Vec<(String, String)>>);
+
+impl log::Log for Capture {
+    fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        let line = (record.target().to_owned(), record.args().to_string());
+        self.0.lock().unwrap().push(line);
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+#[derive(Deserialize)]
+struct Config {
+    server: Server,
+}
+
+#[derive(Deserialize)]
+struct Server {
+    port: u16,
+}
+
+/// The target of the first line logged for a method.
+fn target<'a>(lines: &'a [(String, String)], method: &str) -> &'a str {
+    lines
+        .iter()
+        .find(|(_, line)| line.trim_start().contains(&format!(": {}", method)))
+        .map(|(target, _)| target.as_str())
+        .unwrap_or_else(|| panic!("no `{}` in {:#?}", method, lines))
+}
+
+// A single test as the logger is global.
+#[test]
+fn targets() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let json = r#"[{"server": {"port": 80}}]"#;
+    let configs: Vec<Config> =
+        serde_log::deserialize(&mut serde_json::Deserializer::from_str(json)).unwrap();
+    assert_eq!(configs[0].server.port, 80);
+
+    let lines = std::mem::take(&mut *LOGGER.0.lock().unwrap());
+    // Outside of any struct, after the top-level type.
+    assert_eq!(target(&lines, "deserialize_seq"), "serde_log::Vec");
+    // Only the innermost struct names the target.
+    assert_eq!(target(&lines, "deserialize_struct"), "serde_log::Config");
+    assert_eq!(target(&lines, "deserialize_u16"), "serde_log::Server");
+    assert!(lines
+        .iter()
+        .all(|(target, _)| !target.contains("Config::Server")));
+
+    let config = serde_log::Config::new().target("app");
+    let port: u16 = config
+        .deserialize(&mut serde_json::Deserializer::from_str("80"))
+        .unwrap();
+    assert_eq!(port, 80);
+    let lines = std::mem::take(&mut *LOGGER.0.lock().unwrap());
+    assert_eq!(target(&lines, "deserialize_u16"), "app::u16");
+}
@@ -0,0 +1,68 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::{Deserialize, Deserializer};
+use serde_log::Kind;
+use std::{cmp::Ordering, collections::BTreeMap};
+
+/// The path of the visitor call given `value` deserializing `json` as a `T`.
+fn path_of<'de, T>(json: &'de str, value: &str) -> String
+where
+    T: Deserialize<'de>,
+{
+    let (result, trace) = serde_log::record::<_, T>(&mut serde_json::Deserializer::from_str(json));
+    assert!(result.is_ok());
+    trace
+        .iter()
+        .filter(|r| r.kind == Kind::Visitor)
+        .find(|r| r.value.as_ref().map(ToString::to_string).as_deref() == Some(value))
+        .map(|r| r.path.to_string())
+        .unwrap_or_else(|| panic!("no visit of {} in\n{}", value, trace))
+}
+
+/// A float map key, ordered by `total_cmp`.
+#[derive(Debug, PartialEq)]
+struct Key(f64);
+
+impl Eq for Key {}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        f64::deserialize(deserializer).map(Key)
+    }
+}
+
+#[test]
+fn string_keys() {
+    let json = r#"{"a": [{"b": 1}]}"#;
+    assert_eq!(
+        path_of::<BTreeMap<String, Vec<BTreeMap<String, u32>>>>(json, "1"),
+        "/a/0/b"
+    );
+}
+
+#[test]
+fn non_string_keys() {
+    assert_eq!(
+        path_of::<BTreeMap<bool, u32>>(r#"{"true": 1}"#, "1"),
+        "/true"
+    );
+    assert_eq!(path_of::<BTreeMap<u8, u32>>(r#"{"7": 1}"#, "1"), "/7");
+    assert_eq!(path_of::<BTreeMap<i64, u32>>(r#"{"-7": 1}"#, "1"), "/-7");
+    assert_eq!(path_of::<BTreeMap<char, u32>>(r#"{"c": 1}"#, "1"), "/c");
+    assert_eq!(path_of::<BTreeMap<Key, u32>>(r#"{"1.5": 1}"#, "1"), "/1.5");
+}
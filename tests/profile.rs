@@ -0,0 +1,123 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::{Deserialize, Deserializer};
+use serde_log::{Profile, Timing};
+
+/// A value with its own `Deserialize`, nesting a call for another type.
+#[derive(Debug)]
+#[allow(dead_code)]
+struct Custom(u32);
+
+impl<'de> Deserialize<'de> for Custom {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        u32::deserialize(deserializer).map(Custom)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Outer {
+    a: Inner,
+    b: Vec<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Inner {
+    custom: Custom,
+    plain: u32,
+}
+
+// The timings themselves are checked against synthetic events in the unit tests, these only
+// rely on how the calls nest as a coarse clock may time them all as zero.
+fn profile() -> Profile {
+    let json = r#"{"a": {"custom": 1, "plain": 2}, "b": [3, 4]}"#;
+    let (result, profile) =
+        serde_log::profile::<_, Outer>(&mut serde_json::Deserializer::from_str(json));
+    assert!(result.is_ok());
+    profile
+}
+
+fn timing<'a, K>(rows: &'a [(K, Timing)], key: &str) -> &'a Timing
+where
+    K: ToString,
+{
+    rows.iter()
+        .find(|(k, _)| k.to_string() == key)
+        .map(|(_, timing)| timing)
+        .unwrap_or_else(|| panic!("no {}", key))
+}
+
+/// Checks the rows are ranked and each total includes its self time.
+fn assert_ranked<K>(rows: &[(K, Timing)])
+where
+    K: ToString,
+{
+    for pair in rows.windows(2) {
+        assert!(pair[0].1.total >= pair[1].1.total);
+    }
+    for (key, timing) in rows {
+        assert!(timing.total >= timing.self_time, "{}", key.to_string());
+        assert!(timing.calls > 0, "{}", key.to_string());
+    }
+}
+
+#[test]
+fn paths() {
+    let profile = profile();
+    let paths = profile.paths();
+    assert_ranked(paths);
+
+    let mut names: Vec<_> = paths.iter().map(|(p, _)| p.to_string()).collect();
+    names.sort();
+    assert_eq!(
+        names,
+        ["/", "/a", "/a/custom", "/a/plain", "/b", "/b/0", "/b/1"]
+    );
+    // A subtree includes the paths nested in it.
+    let root = timing(paths, "/");
+    let a = timing(paths, "/a");
+    assert!(root.total >= a.total);
+    assert!(a.total >= timing(paths, "/a/custom").total);
+    assert!(root.total >= timing(paths, "/b").total);
+    assert_eq!(timing(paths, "/a/custom").calls, 3);
+    assert_eq!(timing(paths, "/b/1").calls, 3);
+}
+
+#[test]
+fn types() {
+    let profile = profile();
+    let types = profile.types();
+    assert_ranked(types);
+
+    let outer = timing(types, "profile::Outer");
+    let inner = timing(types, "profile::Inner");
+    let custom = timing(types, "profile::Custom");
+    assert!(outer.total >= inner.total);
+    assert!(inner.total >= custom.total);
+    // `next_value` and the `deserialize` nested in it.
+    assert_eq!(custom.calls, 2);
+    assert_eq!(timing(types, "alloc::vec::Vec<u32>").calls, 4);
+    assert_eq!(timing(types, "u32").calls, 15);
+}
+
+#[test]
+fn report() {
+    let profile = profile();
+    let report = profile.report(2).to_string();
+    let lines: Vec<_> = report.lines().collect();
+    assert_eq!(lines.len(), 7, "{}", report);
+    assert!(lines[0].ends_with("  path"));
+    for (line, (path, timing)) in lines[1..3].iter().zip(profile.paths()) {
+        assert!(line.ends_with(&format!("  {}", path)), "{}", line);
+        assert!(line.contains(&format!(" {}  ", timing.calls)), "{}", line);
+    }
+    assert_eq!(lines[3], "");
+    assert!(lines[4].ends_with("  type"));
+    for (line, (name, _)) in lines[5..].iter().zip(profile.types()) {
+        assert!(line.ends_with(&format!("  {}", name)), "{}", line);
+    }
+}
@@ -0,0 +1,103 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::{Deserialize, Serialize};
+use serde_log::{Config, Redaction};
+use std::sync::Mutex;
+
+const SECRET: &str = "hunter2";
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Login {
+    user: String,
+    password: String,
+    port: u16,
+}
+
+fn config() -> Config {
+    Config::new().redact("password")
+}
+
+#[test]
+fn trace() {
+    for redaction in [Redaction::Mask, Redaction::Hash] {
+        let json = r#"{"user": "admin", "password": "hunter2", "port": 22}"#;
+        let (login, trace) = config()
+            .redaction(redaction)
+            .record::<_, Login>(&mut serde_json::Deserializer::from_str(json));
+        assert_eq!(login.unwrap().password, SECRET);
+        assert!(trace.contains("visit_borrowed_str"));
+        assert!(!trace.to_string().contains(SECRET), "{}", trace);
+        assert!(!format!("{:?}", trace).contains(SECRET));
+        assert!(trace.to_string().contains("admin"));
+    }
+}
+
+#[test]
+fn error_context() {
+    // The error quotes the value, which must not show in the message nor in the events.
+    let json = r#"{"user": "admin", "password": "hunter2", "port": 22}"#;
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Numeric {
+        user: String,
+        password: u32,
+    }
+    let err = config()
+        .deserialize_with_context::<_, Numeric>(&mut serde_json::Deserializer::from_str(json))
+        .unwrap_err();
+    assert_eq!(err.path().to_string(), "/password");
+    assert!(!err.events().is_empty());
+    for event in err.events() {
+        assert!(!event.contains(SECRET), "{}", event);
+    }
+    assert!(err.to_string().contains("/password"));
+}
+
+/// Keeps every message logged.
+struct Capture(Mutex<Vec<String>>);
+
+impl log::Log for Capture {
+    fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        self.0.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: Capture = Capture(Mutex::new(Vec::new()));
+
+// A single test as the logger is global.
+#[test]
+fn logged() {
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(log::LevelFilter::Trace);
+
+    let login = Login {
+        user: "admin".to_owned(),
+        password: SECRET.to_owned(),
+        port: 22,
+    };
+    let mut out = Vec::new();
+    config()
+        .serialize(&login, &mut serde_json::Serializer::new(&mut out))
+        .unwrap();
+    // The output itself is untouched.
+    assert_eq!(out, serde_json::to_vec(&login).unwrap());
+
+    let json = String::from_utf8(out).unwrap();
+    let _: Login = config()
+        .deserialize(&mut serde_json::Deserializer::from_str(&json))
+        .unwrap();
+
+    let lines = LOGGER.0.lock().unwrap();
+    assert!(lines.iter().any(|l| l.contains("serialize_str")));
+    assert!(lines.iter().any(|l| l.contains("visit_borrowed_str")));
+    assert!(lines.iter().any(|l| l.contains("<redacted>")));
+    for line in lines.iter() {
+        assert!(!line.contains(SECRET), "{}", line);
+    }
+}
@@ -0,0 +1,118 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::Deserialize;
+use serde_log::{Config, Trace, Value};
+
+fn record<'de, T>(json: &'de str, head: usize, tail: usize) -> (T, Trace)
+where
+    T: Deserialize<'de>,
+{
+    let (result, trace) = Config::new()
+        .sample(head, tail)
+        .record(&mut serde_json::Deserializer::from_str(json));
+    (result.unwrap(), trace)
+}
+
+/// The numbers visited, in order.
+fn visited(trace: &Trace) -> Vec<String> {
+    trace
+        .iter()
+        .filter(|r| r.method == "visit_u64" && !r.is_exit())
+        .map(|r| r.value.as_ref().unwrap().to_string())
+        .collect()
+}
+
+/// The path and count of the `elided` events, in order.
+fn elided(trace: &Trace) -> Vec<(String, u64)> {
+    trace
+        .iter()
+        .filter(|r| r.method == "elided")
+        .map(|r| {
+            let count = match r.args.iter().find(|(name, _)| name == "count") {
+                Some((_, Value::U64(count))) => *count,
+                other => panic!("unexpected count {:?}", other),
+            };
+            (r.path.to_string(), count)
+        })
+        .collect()
+}
+
+#[test]
+fn fewer_than_head_and_tail() {
+    let (value, trace) = record::<Vec<u32>>("[1, 2]", 2, 1);
+    assert_eq!(value, [1, 2]);
+    assert_eq!(visited(&trace), ["1", "2"]);
+    assert_eq!(elided(&trace), []);
+}
+
+#[test]
+fn as_many_as_head_and_tail() {
+    let (value, trace) = record::<Vec<u32>>("[1, 2, 3]", 2, 1);
+    assert_eq!(value, [1, 2, 3]);
+    assert_eq!(visited(&trace), ["1", "2", "3"]);
+    assert_eq!(elided(&trace), []);
+}
+
+#[test]
+fn more_than_head_and_tail() {
+    let (value, trace) = record::<Vec<u32>>("[1, 2, 3, 4, 5, 6]", 2, 1);
+    assert_eq!(value, [1, 2, 3, 4, 5, 6]);
+    assert_eq!(visited(&trace), ["1", "2", "6"]);
+    assert_eq!(elided(&trace), [("/".to_owned(), 3)]);
+}
+
+#[test]
+fn head_only() {
+    let (_, trace) = record::<Vec<u32>>("[1, 2, 3, 4]", 1, 0);
+    assert_eq!(visited(&trace), ["1"]);
+    assert_eq!(elided(&trace), [("/".to_owned(), 3)]);
+}
+
+#[test]
+fn tail_replayed_in_order() {
+    let (_, trace) = record::<Vec<u32>>("[1, 2, 3, 4, 5, 6]", 1, 2);
+    assert_eq!(visited(&trace), ["1", "5", "6"]);
+
+    let methods: Vec<_> = trace.methods().collect();
+    let last_visit = methods.iter().rposition(|m| *m == "visit_u64").unwrap();
+    let elided_at = methods.iter().position(|m| *m == "elided").unwrap();
+    // The tail, then how many were left out, then the end of the sequence.
+    assert!(last_visit < elided_at);
+    let end = trace
+        .iter()
+        .rposition(|r| r.method == "next_element" && !r.is_exit())
+        .unwrap();
+    assert!(elided_at < end);
+
+    // The elements keep their paths.
+    let paths: Vec<_> = trace
+        .iter()
+        .filter(|r| r.method == "visit_u64" && !r.is_exit())
+        .map(|r| r.path.to_string())
+        .collect();
+    assert_eq!(paths, ["/0", "/4", "/5"]);
+}
+
+#[test]
+fn maps() {
+    let json = r#"{"a": 1, "b": 2, "c": 3, "d": 4}"#;
+    let (_, trace) = record::<std::collections::BTreeMap<String, u32>>(json, 1, 1);
+    assert_eq!(visited(&trace), ["1", "4"]);
+    assert_eq!(elided(&trace), [("/".to_owned(), 2)]);
+}
+
+#[test]
+fn nested() {
+    let json = "[[1, 2, 3], [4, 5, 6], [7, 8, 9]]";
+    let (value, trace) = record::<Vec<Vec<u32>>>(json, 1, 1);
+    assert_eq!(value.concat(), [1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    assert_eq!(visited(&trace), ["1", "3", "7", "9"]);
+    assert_eq!(
+        elided(&trace),
+        [
+            ("/0".to_owned(), 1),
+            ("/2".to_owned(), 1),
+            ("/".to_owned(), 1),
+        ]
+    );
+}
@@ -0,0 +1,188 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::Serialize;
+use serde_log::{sink::Collector, Kind, Trace};
+use std::{collections::BTreeMap, sync::Arc};
+
+#[derive(Serialize)]
+struct Package {
+    name: &'static str,
+    tags: Vec<u8>,
+    deps: BTreeMap<&'static str, u32>,
+    pair: (u8, char),
+    license: Option<()>,
+}
+
+#[derive(Serialize)]
+enum Shape {
+    Empty,
+    Circle(u8),
+    Line(u8, u8),
+    Rect { w: u8 },
+}
+
+/// Serializes `value` to JSON, returning the JSON and the events.
+fn record<T>(value: &T) -> (String, Trace)
+where
+    T: Serialize,
+{
+    let collector = Arc::new(Collector::new());
+    let mut out = Vec::new();
+    let mut serializer = serde_json::Serializer::new(&mut out);
+    value
+        .serialize(serde_log::Serializer::with_sink(
+            &mut serializer,
+            collector.clone(),
+        ))
+        .unwrap();
+    (
+        String::from_utf8(out).unwrap(),
+        Trace::from(collector.take()),
+    )
+}
+
+/// Every event as `<event> at <path>`.
+fn lines(trace: &Trace) -> Vec<String> {
+    trace
+        .iter()
+        .map(|r| format!("{} at {}", r, r.path))
+        .collect()
+}
+
+#[test]
+fn struct_events() {
+    let mut deps = BTreeMap::new();
+    deps.insert("log", 4);
+    let package = Package {
+        name: "serde_log",
+        tags: vec![1],
+        deps,
+        pair: (2, 'c'),
+        license: None,
+    };
+    let (json, trace) = record(&package);
+    assert_eq!(json, serde_json::to_string(&package).unwrap());
+    assert_eq!(
+        lines(&trace),
+        [
+            "Serializer: serialize_struct(name=\"Package\", len=5) at /",
+            "SerializeStruct: serialize_field(key=\"name\") at /",
+            "Serialize: serialize at /name",
+            "Serializer: serialize_str(\"serde_log\") at /name",
+            "SerializeStruct: serialize_field(key=\"tags\") at /",
+            "Serialize: serialize at /tags",
+            "Serializer: serialize_seq(len=1) at /tags",
+            "SerializeSeq: serialize_element at /tags",
+            "Serialize: serialize at /tags/0",
+            "Serializer: serialize_u8(1) at /tags/0",
+            "SerializeSeq: end at /tags",
+            "SerializeStruct: serialize_field(key=\"deps\") at /",
+            "Serialize: serialize at /deps",
+            "Serializer: serialize_map(len=1) at /deps",
+            "SerializeMap: serialize_entry at /deps",
+            "Serialize: serialize at /deps",
+            "Serializer: serialize_str(\"log\") at /deps",
+            "Serialize: serialize at /deps/log",
+            "Serializer: serialize_u32(4) at /deps/log",
+            "SerializeMap: end at /deps",
+            "SerializeStruct: serialize_field(key=\"pair\") at /",
+            "Serialize: serialize at /pair",
+            "Serializer: serialize_tuple(len=2) at /pair",
+            "SerializeTuple: serialize_element at /pair",
+            "Serialize: serialize at /pair/0",
+            "Serializer: serialize_u8(2) at /pair/0",
+            "SerializeTuple: serialize_element at /pair",
+            "Serialize: serialize at /pair/1",
+            "Serializer: serialize_char('c') at /pair/1",
+            "SerializeTuple: end at /pair",
+            "SerializeStruct: serialize_field(key=\"license\") at /",
+            "Serialize: serialize at /license",
+            "Serializer: serialize_none at /license",
+            "SerializeStruct: end at /",
+        ]
+    );
+}
+
+#[test]
+fn variant_events() {
+    let shapes = [
+        Shape::Empty,
+        Shape::Circle(1),
+        Shape::Line(2, 3),
+        Shape::Rect { w: 4 },
+    ];
+    let (json, trace) = record(&shapes);
+    assert_eq!(json, serde_json::to_string(&shapes).unwrap());
+
+    let variants: Vec<_> = trace
+        .of_kind(Kind::Serializer)
+        .filter(|r| r.method.ends_with("_variant"))
+        .map(|r| format!("{} at {}", r, r.path))
+        .collect();
+    assert_eq!(
+        variants,
+        [
+            "Serializer: serialize_unit_variant(name=\"Shape\", variant_index=0, \
+             variant=\"Empty\") at /0",
+            "Serializer: serialize_newtype_variant(name=\"Shape\", variant_index=1, \
+             variant=\"Circle\") at /1",
+            "Serializer: serialize_tuple_variant(name=\"Shape\", variant_index=2, \
+             variant=\"Line\", len=2) at /2",
+            "Serializer: serialize_struct_variant(name=\"Shape\", variant_index=3, \
+             variant=\"Rect\", len=1) at /3",
+        ]
+    );
+
+    // The payloads are nested under the variants.
+    let values: Vec<_> = trace
+        .iter()
+        .filter(|r| r.method == "serialize_u8")
+        .map(|r| format!("{} at {}", r.value.as_ref().unwrap(), r.path))
+        .collect();
+    assert_eq!(
+        values,
+        [
+            "1 at /1/Circle",
+            "2 at /2/Line/0",
+            "3 at /2/Line/1",
+            "4 at /3/Rect/w"
+        ]
+    );
+    assert_eq!(trace.count("serialize_field"), 3);
+    assert_eq!(trace.of_kind(Kind::SerializeTupleVariant).count(), 3);
+    assert_eq!(trace.of_kind(Kind::SerializeStructVariant).count(), 2);
+}
+
+/// A map with float keys, which have no `Ord` for a `BTreeMap`.
+struct FloatKeys(Vec<(f64, u8)>);
+
+impl Serialize for FloatKeys {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_map(self.0.iter().map(|(k, v)| (k, v)))
+    }
+}
+
+/// The paths of the `serialize_u8` calls when serializing `value`.
+fn u8_paths<T>(value: &T) -> Vec<String>
+where
+    T: Serialize,
+{
+    let (_, trace) = record(value);
+    trace
+        .iter()
+        .filter(|r| r.method == "serialize_u8")
+        .map(|r| r.path.to_string())
+        .collect()
+}
+
+#[test]
+fn non_string_keys() {
+    let mut bools = BTreeMap::new();
+    bools.insert(false, 1u8);
+    bools.insert(true, 2u8);
+    assert_eq!(u8_paths(&bools), ["/false", "/true"]);
+    assert_eq!(u8_paths(&FloatKeys(vec![(1.5, 1)])), ["/1.5"]);
+}
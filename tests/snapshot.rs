@@ -0,0 +1,62 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::{de, Deserialize, Deserializer};
+use std::fmt;
+
+/// A `major.minor` version read from a string, the kind of impl the snapshots guard.
+#[derive(Debug, PartialEq)]
+struct Version(u16, u16);
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct VersionVisitor;
+
+        impl de::Visitor<'_> for VersionVisitor {
+            type Value = Version;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a version")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                let parse = |s: Option<&str>| s.and_then(|s| s.parse().ok());
+                let mut parts = v.splitn(2, '.');
+                match (parse(parts.next()), parse(parts.next())) {
+                    (Some(major), Some(minor)) => Ok(Version(major, minor)),
+                    _ => Err(E::invalid_value(de::Unexpected::Str(v), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(VersionVisitor)
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+struct Package {
+    name: String,
+    version: Version,
+    features: Vec<String>,
+}
+
+#[test]
+fn snapshot_json() {
+    let json = r#"{"name": "serde_log", "version": "0.1", "features": ["tracing"]}"#;
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let package: Package = serde_log::assert_trace_snapshot!("package", &mut deserializer).unwrap();
+    assert_eq!(package.version, Version(0, 1));
+}
+
+#[test]
+fn snapshot_error() {
+    let mut deserializer = serde_json::Deserializer::from_str(r#""0.x""#);
+    let result: Result<Version, _> =
+        serde_log::assert_trace_snapshot!("version_error", &mut deserializer);
+    assert!(result.is_err());
+}
@@ -0,0 +1,83 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::Deserialize;
+use serde_log::{Outcome, Stats};
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Package {
+    name: String,
+    tags: Vec<String>,
+    version: u8,
+}
+
+fn stats(json: &str) -> (Result<Package, serde_json::Error>, Stats) {
+    serde_log::deserialize_with_stats(&mut serde_json::Deserializer::from_str(json))
+}
+
+#[test]
+fn tally() {
+    // The escape makes serde_json hand out a temporary copy of the name.
+    let (result, stats) = stats(r#"{"name": "a\nb", "tags": ["x", "yz"], "version": 1}"#);
+    assert!(result.is_ok());
+
+    let calls: Vec<_> = stats.calls.iter().map(|(m, n)| (m.as_str(), *n)).collect();
+    assert_eq!(
+        calls,
+        [
+            ("deserialize", 8),
+            ("deserialize_identifier", 3),
+            ("deserialize_seq", 1),
+            ("deserialize_string", 3),
+            ("deserialize_struct", 1),
+            ("deserialize_u8", 1),
+            ("next_element", 3),
+            ("next_key", 4),
+            ("next_value", 3),
+            ("visit_borrowed_str", 5),
+            ("visit_map", 1),
+            ("visit_seq", 1),
+            ("visit_str", 1),
+            ("visit_u64", 1),
+        ]
+    );
+    assert_eq!(stats.total_calls(), 36);
+    assert_eq!(stats.elements, 2);
+    assert_eq!(stats.keys, 3);
+    // The keys and the strings: 4 + 3 + 4 + 1 + 2 + 7.
+    assert_eq!(stats.payload_bytes, 21);
+    assert_eq!(stats.borrowed, 5);
+    assert_eq!(stats.owned, 1);
+    assert_eq!(stats.max_depth, 9);
+    assert_eq!(stats.outcome, Outcome::Ok);
+    assert!(stats.elapsed > std::time::Duration::ZERO);
+}
+
+#[test]
+fn empty_collections() {
+    let (result, stats) = stats(r#"{"name": "", "tags": [], "version": 1}"#);
+    assert!(result.is_ok());
+    assert_eq!(stats.elements, 0);
+    assert_eq!(stats.keys, 3);
+    assert_eq!(stats.calls["next_element"], 1);
+    assert_eq!(stats.payload_bytes, 4 + 4 + 7);
+}
+
+#[test]
+fn failure() {
+    let (result, stats) = stats(r#"{"name": "a", "tags": [1]}"#);
+    assert!(result.is_err());
+    match &stats.outcome {
+        Outcome::Err(e) => assert!(e.contains("invalid type: integer `1`"), "{}", e),
+        Outcome::Ok => panic!("expected an error"),
+    }
+    assert_eq!(stats.elements, 1);
+
+    let shown = stats.to_string();
+    assert!(
+        shown.starts_with("Err(invalid type: integer `1`"),
+        "{}",
+        shown
+    );
+    assert!(shown.contains(", 1 elements, 2 keys, "), "{}", shown);
+}
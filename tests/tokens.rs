@@ -0,0 +1,125 @@
+#![cfg(all(feature = "serde_test", not(feature = "disabled")))]
+
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+use serde_test::{assert_de_tokens, Token};
+use std::fmt::Debug;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Point {
+    x: i32,
+    label: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Meters(u16);
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Line(u8, u8),
+    Rect { w: u8, h: u8 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Drawing {
+    name: String,
+    scale: Meters,
+    shapes: Vec<Shape>,
+}
+
+/// The tokens of the trace of deserializing `json`, checked to give the same value back.
+fn from_json<T>(json: &str) -> Vec<Token>
+where
+    T: for<'de> Deserialize<'de> + Debug + PartialEq,
+{
+    let (result, trace) = serde_log::record::<_, T>(&mut serde_json::Deserializer::from_str(json));
+    let tokens = trace.to_tokens().unwrap();
+    assert_de_tokens(&result.unwrap(), &tokens);
+    tokens
+}
+
+#[test]
+fn struct_tokens() {
+    let tokens = from_json::<Point>(r#"{"x": -1, "label": "a"}"#);
+    assert_eq!(
+        tokens,
+        [
+            Token::Struct {
+                name: "Point",
+                len: 2
+            },
+            Token::BorrowedStr("x"),
+            Token::I64(-1),
+            Token::BorrowedStr("label"),
+            Token::BorrowedStr("a"),
+            Token::StructEnd,
+        ]
+    );
+}
+
+#[test]
+fn newtype_tokens() {
+    let tokens = from_json::<Meters>("3");
+    assert_eq!(
+        tokens,
+        [Token::NewtypeStruct { name: "Meters" }, Token::U64(3)]
+    );
+}
+
+#[test]
+fn variant_tokens() {
+    let json = r#"["Empty", {"Line": [1, 2]}, {"Rect": {"w": 3, "h": 4}}]"#;
+    let tokens = from_json::<Vec<Shape>>(json);
+    assert_eq!(
+        tokens,
+        [
+            Token::Seq { len: Some(3) },
+            Token::UnitVariant {
+                name: "Shape",
+                variant: "Empty"
+            },
+            Token::TupleVariant {
+                name: "Shape",
+                variant: "Line",
+                len: 2
+            },
+            Token::U64(1),
+            Token::U64(2),
+            Token::TupleVariantEnd,
+            Token::StructVariant {
+                name: "Shape",
+                variant: "Rect",
+                len: 2
+            },
+            Token::BorrowedStr("w"),
+            Token::U64(3),
+            Token::BorrowedStr("h"),
+            Token::U64(4),
+            Token::StructVariantEnd,
+            Token::SeqEnd,
+        ]
+    );
+}
+
+#[test]
+fn bincode_tokens() {
+    let drawing = Drawing {
+        name: "plan".to_owned(),
+        scale: Meters(100),
+        shapes: vec![Shape::Empty, Shape::Line(1, 2), Shape::Rect { w: 3, h: 4 }],
+    };
+    let bytes = bincode::DefaultOptions::new().serialize(&drawing).unwrap();
+    let mut deserializer =
+        bincode::Deserializer::from_slice(&bytes, bincode::DefaultOptions::new());
+    let (result, trace) = serde_log::record::<_, Drawing>(&mut deserializer);
+    assert_eq!(result.unwrap(), drawing);
+
+    // The fields and variants are read by position, and still come out as tokens.
+    let tokens = trace.to_tokens().unwrap();
+    assert_de_tokens(&drawing, &tokens);
+    assert!(tokens.contains(&Token::UnitVariant {
+        name: "Shape",
+        variant: "Empty"
+    }));
+}
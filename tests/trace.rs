@@ -0,0 +1,72 @@
+#![cfg(not(feature = "disabled"))]
+
+use serde::{Deserialize, Serialize};
+use serde_log::{Trace, Value};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Shape {
+    Empty,
+    Circle(u8),
+    Rect { w: u8 },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Doc {
+    name: String,
+    raw: Vec<u8>,
+    flags: Option<(bool, f64, char)>,
+    shapes: Vec<Shape>,
+}
+
+fn trace() -> Trace {
+    let json = r#"{"name": "a\nb", "raw": [1, 2], "flags": [true, 0.5, "x"],
+        "shapes": ["Empty", {"Circle": 1}, {"Rect": {"w": 2}}], "bad": -1}"#;
+    let (result, trace) =
+        serde_log::record::<_, Doc>(&mut serde_json::Deserializer::from_str(json));
+    assert!(result.is_ok());
+    trace
+}
+
+#[test]
+fn json_round_trip() {
+    let trace = trace();
+    let saved = serde_json::to_string(&trace).unwrap();
+    assert!(saved.starts_with(
+        r#"[{"kind":"Deserializer","method":"deserialize_struct","type_name":"trace::Doc","#
+    ));
+    assert_eq!(serde_json::from_str::<Trace>(&saved).unwrap(), trace);
+}
+
+#[test]
+fn binary_round_trip() {
+    let trace = trace();
+    let saved = bincode::serialize(&trace).unwrap();
+    assert_eq!(bincode::deserialize::<Trace>(&saved).unwrap(), trace);
+    let saved = postcard::to_allocvec(&trace).unwrap();
+    assert_eq!(postcard::from_bytes::<Trace>(&saved).unwrap(), trace);
+}
+
+#[test]
+fn optional_fields() {
+    // Older traces have no `enter`, and the options can be left out.
+    let json = r#"[{"kind": "Visitor", "method": "visit_u64", "type_name": "u8",
+        "scope": null, "depth": 1, "path": [{"Key": "a"}, {"Index": 0}],
+        "args": [["len", {"U64": 2}]], "value": {"U64": 1}, "unknown": 0}]"#;
+    let trace: Trace = serde_json::from_str(json).unwrap();
+    let record = &trace.records()[0];
+    assert_eq!(record.to_string(), "Visitor: visit_u64(len=2, 1) -> u8");
+    assert_eq!(record.path.to_string(), "/a/0");
+    assert_eq!(record.args, [("len".into(), Value::U64(2))]);
+    assert!(!record.is_enter());
+    assert!(!record.is_exit());
+}
+
+#[test]
+fn unknown_variant() {
+    let err = serde_json::from_str::<Value>(r#"{"U256": 1}"#).unwrap_err();
+    assert!(
+        err.to_string().starts_with("unknown variant `U256`"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,285 @@
+#![cfg(all(feature = "tracing", not(feature = "disabled")))]
+
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// Collects the `method` field and the message of the `serde_log` events, and every span.
+#[derive(Clone, Default)]
+struct Capture {
+    methods: Arc<Mutex<Vec<String>>>,
+    messages: Arc<Mutex<Vec<String>>>,
+    spans: Arc<Mutex<Vec<SpanData>>>,
+    entered: Arc<Mutex<Vec<span::Id>>>,
+}
+
+/// A span with its fields and the index of its parent.
+#[derive(Debug)]
+struct SpanData {
+    name: &'static str,
+    fields: BTreeMap<&'static str, String>,
+    parent: Option<usize>,
+}
+
+impl Visit for SpanData {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields.insert(field.name(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields.insert(field.name(), format!("{:?}", value));
+    }
+}
+
+#[derive(Default)]
+struct Fields {
+    method: Option<String>,
+    message: Option<String>,
+}
+
+impl Visit for Fields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "method" {
+            self.method = Some(value.to_owned());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        }
+    }
+}
+
+impl Subscriber for Capture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &span::Attributes<'_>) -> span::Id {
+        let parent = match attrs.parent() {
+            Some(parent) => Some(parent.clone()),
+            None if attrs.is_contextual() => self.entered.lock().unwrap().last().cloned(),
+            None => None,
+        };
+        let mut data = SpanData {
+            name: attrs.metadata().name(),
+            fields: BTreeMap::new(),
+            parent: parent.map(|id| id.into_u64() as usize - 1),
+        };
+        attrs.record(&mut data);
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(data);
+        span::Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, id: &span::Id, values: &span::Record<'_>) {
+        values.record(&mut self.spans.lock().unwrap()[id.into_u64() as usize - 1]);
+    }
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        if event.metadata().target() != "serde_log" {
+            return;
+        }
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        self.methods.lock().unwrap().extend(fields.method);
+        self.messages.lock().unwrap().extend(fields.message);
+    }
+
+    fn enter(&self, id: &span::Id) {
+        self.entered.lock().unwrap().push(id.clone());
+    }
+
+    fn exit(&self, id: &span::Id) {
+        let mut entered = self.entered.lock().unwrap();
+        if let Some(i) = entered.iter().rposition(|e| e == id) {
+            entered.remove(i);
+        }
+    }
+}
+
+#[test]
+fn emits_tracing_events() {
+    let capture = Capture::default();
+    let value: Vec<u32> = tracing::subscriber::with_default(capture.clone(), || {
+        serde_log::deserialize(&mut serde_json::Deserializer::from_str("[1, 2]")).unwrap()
+    });
+    assert_eq!(value, [1, 2]);
+
+    let methods = capture.methods.lock().unwrap();
+    assert!(
+        methods.iter().any(|m| m == "deserialize_seq"),
+        "{:?}",
+        methods
+    );
+    // Enter and exit events.
+    assert_eq!(methods.iter().filter(|m| *m == "visit_u64").count(), 4);
+}
+
+#[test]
+fn config_emits_tracing_events() {
+    let capture = Capture::default();
+    tracing::subscriber::with_default(capture.clone(), || {
+        let config = serde_log::Config::new().visitor_calls(false);
+        let value: bool = config
+            .deserialize(&mut serde_json::Deserializer::from_str("true"))
+            .unwrap();
+        assert!(value);
+    });
+
+    let methods = capture.methods.lock().unwrap();
+    assert!(methods.iter().any(|m| m == "deserialize_bool"));
+    assert!(!methods.iter().any(|m| m == "visit_bool"));
+}
+
+/// The message of the `visit_str` event when deserializing `s` with `config`.
+fn visit_str_message(config: serde_log::Config, s: &str) -> String {
+    let capture = Capture::default();
+    let json = serde_json::to_string(s).unwrap();
+    tracing::subscriber::with_default(capture.clone(), || {
+        let value: String = config
+            .deserialize(&mut serde_json::Deserializer::from_str(&json))
+            .unwrap();
+        assert_eq!(value, s);
+    });
+    let messages = capture.messages.lock().unwrap();
+    messages
+        .iter()
+        .find(|m| m.starts_with("Visitor: visit_"))
+        .unwrap_or_else(|| panic!("no visit in {:?}", messages))
+        .clone()
+}
+
+#[test]
+fn truncates_values() {
+    let long = "x".repeat(100);
+    let message = visit_str_message(serde_log::Config::new(), &long);
+    assert!(
+        message.contains(&format!("{:?}…(100 bytes)", &long[..64])),
+        "{}",
+        message
+    );
+
+    let config = serde_log::Config::new().max_value_len(Some(4));
+    let message = visit_str_message(config, "abcdefgh");
+    assert!(message.contains(r#""abcd"…(8 bytes)"#), "{}", message);
+
+    let config = serde_log::Config::new().max_value_len(None);
+    let message = visit_str_message(config, &long);
+    assert!(message.contains(&format!("{:?}", long)), "{}", message);
+    assert!(!message.contains('…'), "{}", message);
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    name: String,
+    servers: Vec<Server>,
+    mode: Mode,
+    pair: (u8, u8),
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+struct Server {
+    host: String,
+    port: u16,
+}
+
+#[derive(Deserialize)]
+#[allow(dead_code)]
+enum Mode {
+    Fast,
+    Slow(u8),
+}
+
+#[test]
+fn spans() {
+    let capture = Capture::default();
+    let json = r#"{"name": "app", "servers": [{"host": "a", "port": 1}], "mode": {"Slow": 2},
+        "pair": [3, 4]}"#;
+    tracing::subscriber::with_default(capture.clone(), || {
+        serde_log::deserialize::<_, Config>(&mut serde_json::Deserializer::from_str(json)).unwrap();
+    });
+
+    let spans = capture.spans.lock().unwrap();
+    assert!(spans.iter().all(|span| span.name == "deserialize"));
+    let find = |method: &str, path: &str| {
+        spans
+            .iter()
+            .position(|s| s.fields["method"] == method && s.fields["path"] == path)
+            .unwrap_or_else(|| panic!("no {} span at {} in {:#?}", method, path, spans))
+    };
+    // The methods of the spans a span is nested in, innermost first.
+    let nesting = |mut index: usize| {
+        let mut methods = vec![spans[index].fields["method"].as_str()];
+        while let Some(parent) = spans[index].parent {
+            methods.push(&spans[parent].fields["method"]);
+            index = parent;
+        }
+        methods
+    };
+    let fields = |index: usize, names: &[&'static str]| {
+        names
+            .iter()
+            .map(|name| spans[index].fields.get(name).map(String::as_str))
+            .collect::<Vec<_>>()
+    };
+
+    let config = find("deserialize_struct", "/");
+    assert_eq!(spans[config].parent, None);
+    assert_eq!(
+        fields(config, &["name", "fields", "depth"]),
+        [
+            Some("Config"),
+            Some(r#"["name", "servers", "mode", "pair"]"#),
+            Some("0")
+        ]
+    );
+
+    let servers = find("deserialize_seq", "/servers");
+    assert_eq!(nesting(servers), ["deserialize_seq", "deserialize_struct"]);
+    let server = find("deserialize_struct", "/servers/0");
+    assert_eq!(spans[server].parent, Some(servers));
+    assert_eq!(
+        fields(server, &["name", "fields"]),
+        [Some("Server"), Some(r#"["host", "port"]"#)]
+    );
+    assert_eq!(
+        nesting(find("deserialize_u16", "/servers/0/port")),
+        [
+            "deserialize_u16",
+            "deserialize_struct",
+            "deserialize_seq",
+            "deserialize_struct"
+        ]
+    );
+
+    let mode = find("deserialize_enum", "/mode");
+    assert_eq!(spans[mode].parent, Some(config));
+    assert_eq!(
+        fields(mode, &["name", "variants", "len"]),
+        [Some("Mode"), Some(r#"["Fast", "Slow"]"#), None]
+    );
+    assert_eq!(
+        spans[find("deserialize_u8", "/mode/Slow")].parent,
+        Some(mode)
+    );
+
+    let pair = find("deserialize_tuple", "/pair");
+    assert_eq!(fields(pair, &["len", "name"]), [Some("2"), None]);
+    assert_eq!(spans[find("deserialize_u8", "/pair/1")].parent, Some(pair));
+
+    // Every span is closed once deserialized.
+    assert!(capture.entered.lock().unwrap().is_empty());
+}
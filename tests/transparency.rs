@@ -0,0 +1,256 @@
+use postcard::ser_flavors::Flavor;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use serde_log::sink::Collector;
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::Arc,
+};
+
+/// Serializes differently depending on the format, and remembers which way it was read.
+#[derive(Debug, PartialEq)]
+struct Probe(bool);
+
+impl Serialize for Probe {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.serialize_str("human")
+        } else {
+            serializer.serialize_u8(0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Probe {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            String::deserialize(deserializer).map(|_| Probe(true))
+        } else {
+            u8::deserialize(deserializer).map(|_| Probe(false))
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Mode {
+    Unit,
+    Newtype(u32),
+    Tuple(i8, i64),
+    Struct { flag: bool },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Sample {
+    ip: IpAddr,
+    v6: Ipv6Addr,
+    addr: SocketAddr,
+    probe: Probe,
+    name: String,
+    items: Vec<Option<u16>>,
+    modes: Vec<Mode>,
+}
+
+fn sample(human_readable: bool) -> Sample {
+    Sample {
+        ip: IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)),
+        v6: Ipv6Addr::LOCALHOST,
+        addr: "[::1]:8080".parse().unwrap(),
+        probe: Probe(human_readable),
+        name: "sample".into(),
+        items: vec![Some(1), None, Some(3)],
+        modes: vec![
+            Mode::Unit,
+            Mode::Newtype(7),
+            Mode::Tuple(-1, 1 << 40),
+            Mode::Struct { flag: true },
+        ],
+    }
+}
+
+trait Format {
+    const HUMAN_READABLE: bool;
+
+    fn to_vec<T: Serialize>(value: &T, wrapped: bool) -> Vec<u8>;
+
+    fn from_slice<T: DeserializeOwned>(bytes: &[u8], wrapped: bool) -> T;
+}
+
+struct Json;
+
+impl Format for Json {
+    const HUMAN_READABLE: bool = true;
+
+    fn to_vec<T: Serialize>(value: &T, wrapped: bool) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut serializer = serde_json::Serializer::new(&mut out);
+        if wrapped {
+            let sink = Arc::new(Collector::new());
+            let wrapper = serde_log::Serializer::with_sink(&mut serializer, sink.clone());
+            value.serialize(wrapper).unwrap();
+            assert_recorded(&sink);
+        } else {
+            value.serialize(&mut serializer).unwrap();
+        }
+        out
+    }
+
+    fn from_slice<T: DeserializeOwned>(bytes: &[u8], wrapped: bool) -> T {
+        let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+        if wrapped {
+            let sink = Arc::new(Collector::new());
+            let wrapper = serde_log::Deserializer::with_sink(&mut deserializer, sink.clone());
+            let value = T::deserialize(wrapper).unwrap();
+            assert_recorded(&sink);
+            value
+        } else {
+            T::deserialize(&mut deserializer).unwrap()
+        }
+    }
+}
+
+struct Bincode;
+
+impl Format for Bincode {
+    const HUMAN_READABLE: bool = false;
+
+    fn to_vec<T: Serialize>(value: &T, wrapped: bool) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut serializer = bincode::Serializer::new(&mut out, bincode::DefaultOptions::new());
+        if wrapped {
+            let sink = Arc::new(Collector::new());
+            let wrapper = serde_log::Serializer::with_sink(&mut serializer, sink.clone());
+            value.serialize(wrapper).unwrap();
+            assert_recorded(&sink);
+        } else {
+            value.serialize(&mut serializer).unwrap();
+        }
+        out
+    }
+
+    fn from_slice<T: DeserializeOwned>(bytes: &[u8], wrapped: bool) -> T {
+        let mut deserializer =
+            bincode::Deserializer::from_slice(bytes, bincode::DefaultOptions::new());
+        if wrapped {
+            let sink = Arc::new(Collector::new());
+            let wrapper = serde_log::Deserializer::with_sink(&mut deserializer, sink.clone());
+            let value = T::deserialize(wrapper).unwrap();
+            assert_recorded(&sink);
+            value
+        } else {
+            T::deserialize(&mut deserializer).unwrap()
+        }
+    }
+}
+
+struct Postcard;
+
+impl Format for Postcard {
+    const HUMAN_READABLE: bool = false;
+
+    fn to_vec<T: Serialize>(value: &T, wrapped: bool) -> Vec<u8> {
+        let mut serializer = postcard::Serializer {
+            output: postcard::ser_flavors::AllocVec::new(),
+        };
+        if wrapped {
+            let sink = Arc::new(Collector::new());
+            let wrapper = serde_log::Serializer::with_sink(&mut serializer, sink.clone());
+            value.serialize(wrapper).unwrap();
+            assert_recorded(&sink);
+        } else {
+            value.serialize(&mut serializer).unwrap();
+        }
+        serializer.output.finalize().unwrap()
+    }
+
+    fn from_slice<T: DeserializeOwned>(bytes: &[u8], wrapped: bool) -> T {
+        let mut deserializer = postcard::Deserializer::from_bytes(bytes);
+        if wrapped {
+            let sink = Arc::new(Collector::new());
+            let wrapper = serde_log::Deserializer::with_sink(&mut deserializer, sink.clone());
+            let value = T::deserialize(wrapper).unwrap();
+            assert_recorded(&sink);
+            value
+        } else {
+            T::deserialize(&mut deserializer).unwrap()
+        }
+    }
+}
+
+/// Checks the wrapper recorded the calls, or passed them straight through with `disabled`.
+fn assert_recorded(sink: &Collector) {
+    assert_eq!(sink.records().is_empty(), cfg!(feature = "disabled"));
+}
+
+fn assert_transparent<F: Format>() {
+    let value = sample(F::HUMAN_READABLE);
+
+    let plain = F::to_vec(&value, false);
+    assert_eq!(F::to_vec(&value, true), plain);
+
+    assert_eq!(F::from_slice::<Sample>(&plain, false), value);
+    assert_eq!(F::from_slice::<Sample>(&plain, true), value);
+}
+
+#[test]
+fn transparent_json() {
+    assert_transparent::<Json>();
+}
+
+#[test]
+fn transparent_bincode() {
+    assert_transparent::<Bincode>();
+}
+
+#[test]
+fn transparent_postcard() {
+    assert_transparent::<Postcard>();
+}
+
+#[cfg(not(feature = "disabled"))]
+#[test]
+fn logs_is_human_readable() {
+    let bytes = Bincode::to_vec(&Probe(false), false);
+    let mut deserializer =
+        bincode::Deserializer::from_slice(&bytes, bincode::DefaultOptions::new());
+    let (probe, trace) = serde_log::record::<_, Probe>(&mut deserializer);
+    assert_eq!(probe.unwrap(), Probe(false));
+
+    let record = trace
+        .iter()
+        .find(|r| r.method == "is_human_readable")
+        .unwrap();
+    assert_eq!(record.value, Some(serde_log::Value::Bool(false)));
+}
+
+/// Remembers the type of the deserializer it was given.
+struct DeserializerType(&'static str);
+
+impl<'de> Deserialize<'de> for DeserializerType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        serde::de::IgnoredAny::deserialize(deserializer)?;
+        Ok(DeserializerType(std::any::type_name::<D>()))
+    }
+}
+
+#[test]
+fn skips_wrapper_when_logging_is_off() {
+    // No logger is installed in this test binary.
+    assert_eq!(log::max_level(), log::LevelFilter::Off);
+
+    let mut deserializer = serde_json::Deserializer::from_str("[1]");
+    let DeserializerType(name) = serde_log::deserialize(&mut deserializer).unwrap();
+    assert!(!name.contains("Wrapper"), "{}", name);
+
+    let mut deserializer = serde_json::Deserializer::from_str("[1]");
+    let wrapper = serde_log::Deserializer::new(&mut deserializer);
+    let DeserializerType(name) = DeserializerType::deserialize(wrapper).unwrap();
+    assert!(name.contains("Wrapper"), "{}", name);
+}